@@ -1,6 +1,9 @@
+pub mod byteconverter;
 pub mod chmodcalculator;
 pub mod colorhelper;
+pub mod common;
 pub mod copytoclipboard;
+pub mod floatinspector;
 pub mod ipcalculator;
 pub mod navbar;
 pub mod numcalculator;
@@ -0,0 +1,179 @@
+mod bytes;
+
+use crate::components::copytoclipboard::CopyToClipboard;
+
+use self::bytes::Chunk;
+use yew::prelude::*;
+
+pub struct ByteConverter {
+    link: ComponentLink<Self>,
+    text: String,
+    hex: String,
+    dec: String,
+    bin: String,
+    chunks: Vec<Chunk>,
+    error: Option<(Field, String)>,
+}
+
+pub enum ByteConverterMsg {
+    EncodeText(String),
+    DecodeHex(String),
+    DecodeDec(String),
+    DecodeBin(String),
+}
+
+/// The fields showing the byte string as text or as numbers
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Text,
+    Hex,
+    Dec,
+    Bin,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+}
+
+impl Component for ByteConverter {
+    type Message = ByteConverterMsg;
+    type Properties = Props;
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        ByteConverter {
+            link,
+            text: "".to_string(),
+            hex: "".to_string(),
+            dec: "".to_string(),
+            bin: "".to_string(),
+            chunks: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ByteConverterMsg::EncodeText(value) => {
+                self.error = None;
+                self.calc_fields(Field::Text, value.as_bytes());
+                self.text = value;
+            }
+            ByteConverterMsg::DecodeHex(value) => self.convert(Field::Hex, value, 16),
+            ByteConverterMsg::DecodeDec(value) => self.convert(Field::Dec, value, 10),
+            ByteConverterMsg::DecodeBin(value) => self.convert(Field::Bin, value, 2),
+        }
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <form class="uk-grid-small" uk-grid="">
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="bytestext">{ "Text (UTF-8)" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="bytestext" />
+                            <textarea class="uk-textarea"
+                                      id="bytestext"
+                                      rows="3"
+                                      oninput=self.link.callback(|d: InputData| ByteConverterMsg::EncodeText(d.value))
+                                      value=self.text.clone() />
+                        </div>
+                    </div>
+                    { self.view_invalid() }
+                </div>
+                { self.view_field(Field::Hex, ByteConverterMsg::DecodeHex, "byteshex", "Bytes (Hexadecimal)", "48 65 6c 6c 6f", &self.hex) }
+                { self.view_field(Field::Dec, ByteConverterMsg::DecodeDec, "bytesdec", "Bytes (Decimal)", "72 101 108 108 111", &self.dec) }
+                { self.view_field(Field::Bin, ByteConverterMsg::DecodeBin, "bytesbin", "Bytes (Binary)", "01001000 01100101", &self.bin) }
+            </form>
+        }
+    }
+}
+
+impl ByteConverter {
+    /// Reads the bytes of a number field, keeping the other fields and showing the error if it is no valid input
+    fn convert(&mut self, field: Field, value: String, radix: u32) {
+        match bytes::parse_bytes(&value, radix) {
+            Ok(parsed) => {
+                self.error = None;
+                self.calc_fields(field, &parsed);
+            }
+            Err(e) => self.error = Some((field, e)),
+        }
+
+        match field {
+            Field::Text => self.text = value,
+            Field::Hex => self.hex = value,
+            Field::Dec => self.dec = value,
+            Field::Bin => self.bin = value,
+        }
+    }
+
+    /// Writes the bytes to all fields but the one the user is typing in, invalid UTF-8 shows as replacement character
+    fn calc_fields(&mut self, source: Field, bytes: &[u8]) {
+        self.chunks = bytes::decode_utf8(bytes);
+
+        if source != Field::Text { self.text = String::from_utf8_lossy(bytes).into_owned(); }
+        if source != Field::Hex  { self.hex = bytes::format_bytes(bytes, 16); }
+        if source != Field::Dec  { self.dec = bytes::format_bytes(bytes, 10); }
+        if source != Field::Bin  { self.bin = bytes::format_bytes(bytes, 2); }
+    }
+
+    /// Creates the text with its invalid UTF-8 sequences marked as hex escapes if there are any
+    fn view_invalid(&self) -> Html {
+        let offsets = bytes::invalid_offsets(&self.chunks);
+        if offsets.is_empty() {
+            return html! {};
+        }
+
+        let offsets = offsets.iter().map(|offset| offset.to_string()).collect::<Vec<_>>().join(", ");
+
+        html! {
+            <div class="uk-text-small">
+                <div class="uk-text-danger">{ format!("invalid UTF-8 at byte {}", offsets) }</div>
+                <code>
+                    { for self.chunks.iter().map(|chunk| match chunk {
+                        Chunk::Text(text) => html! { { text } },
+                        Chunk::Invalid(invalid) => html! {
+                            <span class="uk-text-danger">
+                                { invalid.iter().map(|byte| format!("\\x{:02x}", byte)).collect::<String>() }
+                            </span>
+                        },
+                    }) }
+                </code>
+            </div>
+        }
+    }
+
+    /// Creates the input of the bytes written as numbers with its error message
+    fn view_field(&self, field: Field, msg: fn(String) -> ByteConverterMsg, id: &str, label: &str, placeholder: &str, value: &str) -> Html {
+        let error = match &self.error {
+            Some((error_field, error)) if *error_field == field => Some(error.clone()),
+            _ => None,
+        };
+        let class = if error.is_some() { "uk-textarea uk-form-danger" } else { "uk-textarea" };
+        let width = if field == Field::Bin { "uk-width-1-1" } else { "uk-width-1-2" };
+
+        html! {
+            <div class=width>
+                <label class="uk-form-label" for=id.to_string()>{ label }</label>
+                <div class="uk-form-controls">
+                    <div class="uk-inline uk-width-expand">
+                        <CopyToClipboard from=id.to_string() />
+                        <textarea class=class
+                                  id=id.to_string()
+                                  rows="3"
+                                  placeholder=placeholder.to_string()
+                                  oninput=self.link.callback(move |d: InputData| msg(d.value))
+                                  value=value.to_string() />
+                    </div>
+                </div>
+                { for error.map(|error| html! { <div class="uk-text-danger uk-text-small">{ error }</div> }) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,114 @@
+/// A run of a decoded byte string, either valid UTF-8 or bytes that are no valid UTF-8
+#[derive(Clone, Debug, PartialEq)]
+pub enum Chunk {
+    Text(String),
+    Invalid(Vec<u8>),
+}
+
+/// Parses a byte string written as hex, decimal, or binary numbers separated by spaces or commas, a hex or binary
+/// byte string may also be written without separators
+pub fn parse_bytes(input: &str, radix: u32) -> Result<Vec<u8>, String> {
+    let digits_per_byte = match radix {
+        16 => 2,
+        2 => 8,
+        _ => 3,
+    };
+    let mut bytes = Vec::new();
+    let mut position = 0;
+
+    for token in input.split(|c: char| c.is_whitespace() || c == ',') {
+        let start = position;
+        position += token.chars().count() + 1;
+
+        let digits = match radix {
+            16 => token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")).or_else(|| token.strip_prefix("\\x")).unwrap_or(token),
+            2 => token.strip_prefix("0b").or_else(|| token.strip_prefix("0B")).unwrap_or(token),
+            _ => token,
+        };
+        if digits.is_empty() {
+            continue;
+        }
+        if let Some((offset, c)) = digits.char_indices().find(|(_, c)| !c.is_digit(radix)) {
+            let offset = token.len() - digits.len() + offset;
+            return Err(format!("'{}' at position {} is not a base {} digit", c, start + offset + 1, radix));
+        }
+
+        // long runs of hex or binary digits are read as sequence of bytes, digits are ASCII here
+        let chunks: Vec<&str> = if radix != 10 && digits.len() > digits_per_byte {
+            if digits.len() % digits_per_byte != 0 {
+                return Err(format!("'{}' at position {} is not a whole number of bytes", token, start + 1));
+            }
+            (0..digits.len()).step_by(digits_per_byte).map(|i| &digits[i..i + digits_per_byte]).collect()
+        } else {
+            vec![digits]
+        };
+
+        for chunk in chunks {
+            match u8::from_str_radix(chunk, radix) {
+                Ok(byte) => bytes.push(byte),
+                Err(_) => return Err(format!("'{}' at position {} is larger than a byte", token, start + 1)),
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Formats the bytes as zero-padded hex or binary, or as decimal numbers separated by spaces
+pub fn format_bytes(bytes: &[u8], radix: u32) -> String {
+    bytes
+        .iter()
+        .map(|byte| match radix {
+            16 => format!("{:02x}", byte),
+            2 => format!("{:08b}", byte),
+            _ => byte.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits the bytes into runs of valid UTF-8 text and invalid bytes
+pub fn decode_utf8(bytes: &[u8]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        match std::str::from_utf8(rest) {
+            Ok(text) => {
+                chunks.push(Chunk::Text(text.to_string()));
+                break;
+            }
+            Err(e) => {
+                let (valid, invalid) = rest.split_at(e.valid_up_to());
+                if !valid.is_empty() {
+                    chunks.push(Chunk::Text(String::from_utf8_lossy(valid).into_owned()));
+                }
+
+                // a truncated sequence at the end has no error length
+                let length = e.error_len().unwrap_or(invalid.len());
+                chunks.push(Chunk::Invalid(invalid[..length].to_vec()));
+                rest = &invalid[length..];
+            }
+        }
+    }
+
+    chunks
+}
+
+/// Returns the byte offsets of the invalid sequences
+pub fn invalid_offsets(chunks: &[Chunk]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut offset = 0;
+
+    for chunk in chunks {
+        match chunk {
+            Chunk::Text(text) => offset += text.len(),
+            Chunk::Invalid(bytes) => {
+                offsets.push(offset);
+                offset += bytes.len();
+            }
+        }
+    }
+
+    offsets
+}
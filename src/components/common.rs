@@ -0,0 +1,2 @@
+pub mod bitwidth;
+pub mod parse;
@@ -0,0 +1,52 @@
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Zero};
+
+/// The selectable fixed bit widths
+pub const WIDTHS: &[u32] = &[8, 16, 32, 64, 128];
+
+/// Wraps the value around to the unsigned bit pattern of the given width, i.e. the value modulo 2^width
+pub fn wrap(value: &BigInt, width: u32) -> BigInt {
+    let modulus = BigInt::one() << width;
+    let rest = value % &modulus;
+    if rest < BigInt::zero() {
+        rest + modulus
+    } else {
+        rest
+    }
+}
+
+/// Interprets the bit pattern of the given width as two's complement
+pub fn to_signed(pattern: &BigInt, width: u32) -> BigInt {
+    if width > 0 && pattern.bit(width as u64 - 1) {
+        pattern - (BigInt::one() << width)
+    } else {
+        pattern.clone()
+    }
+}
+
+/// Returns the number of digits the given radix needs to show all bits of the given width
+pub fn digits(width: u32, radix: u32) -> usize {
+    let bits_per_digit = 32 - (radix - 1).leading_zeros();
+    width.div_ceil(bits_per_digit) as usize
+}
+
+/// Returns the big-endian bytes of the bit pattern zero-padded to the given width
+pub fn to_bytes(pattern: &BigInt, width: u32) -> Vec<u8> {
+    let (_, bytes) = wrap(pattern, width).to_bytes_be();
+    let len = width.div_ceil(8) as usize;
+    let mut padded = vec![0; len.saturating_sub(bytes.len())];
+    padded.extend(bytes);
+    padded
+}
+
+/// Returns the bit pattern with its byte order reversed
+pub fn swap_bytes(pattern: &BigInt, width: u32) -> BigInt {
+    let mut bytes = to_bytes(pattern, width);
+    bytes.reverse();
+    BigInt::from_bytes_be(Sign::Plus, &bytes)
+}
+
+/// Formats bytes as space-separated hex pairs
+pub fn format_bytes<'a>(bytes: impl Iterator<Item = &'a u8>) -> String {
+    bytes.map(|byte| format!("{:02X}", byte)).collect::<Vec<String>>().join(" ")
+}
@@ -0,0 +1,99 @@
+use num_bigint::BigInt;
+use num_traits::Num;
+use std::fmt;
+
+/// Characters allowed between digits for grouping
+const SEPARATORS: &[char] = &['_', ' ', ',', '\'', '\t'];
+
+/// The reason an input could not be read as number
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    NoDigits,
+    InvalidDigit { digit: char, position: usize, radix: u32 },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty input"),
+            ParseError::NoDigits => write!(f, "no digits"),
+            ParseError::InvalidDigit { digit, position, radix } => {
+                write!(f, "'{}' at position {} is not a base {} digit", digit, position, radix)
+            }
+        }
+    }
+}
+
+/// Parses a number in the given radix accepting a sign, a `0x`, `0o`, or `0b` prefix overriding the radix, and
+/// underscores, spaces, commas, or apostrophes as digit grouping
+pub fn parse_lenient(input: &str, radix: u32) -> Result<BigInt, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+    let mut radix = radix;
+
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    if i == chars.len() {
+        return Err(ParseError::Empty);
+    }
+
+    let negative = chars[i] == '-';
+    if chars[i] == '-' || chars[i] == '+' {
+        i += 1;
+    }
+
+    // a prefix only applies if its letter is no digit of the field's radix, e.g. 0b is a hex number
+    if i + 1 < chars.len() && chars[i] == '0' && chars[i + 1].to_digit(radix).is_none() {
+        if let Some(prefix_radix) = prefix_radix(chars[i + 1]) {
+            radix = prefix_radix;
+            i += 2;
+        }
+    }
+
+    let mut digits = String::new();
+    for (position, c) in chars.iter().enumerate().skip(i) {
+        if SEPARATORS.contains(c) {
+            continue;
+        }
+        if c.to_digit(radix).is_none() {
+            return Err(ParseError::InvalidDigit { digit: *c, position: position + 1, radix });
+        }
+        digits.push(*c);
+    }
+
+    if digits.is_empty() {
+        return Err(ParseError::NoDigits);
+    }
+
+    let value = BigInt::from_str_radix(&digits, radix).map_err(|_| ParseError::NoDigits)?;
+    Ok(if negative { -value } else { value })
+}
+
+fn prefix_radix(c: char) -> Option<u32> {
+    match c.to_ascii_lowercase() {
+        'x' => Some(16),
+        'o' => Some(8),
+        'b' => Some(2),
+        _ => None,
+    }
+}
+
+/// Groups the digits from the right with the separator, keeping a leading sign
+pub fn group(digits: &str, size: usize, separator: char) -> String {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits),
+    };
+
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % size == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+
+    format!("{}{}", sign, grouped)
+}
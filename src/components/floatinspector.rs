@@ -0,0 +1,147 @@
+mod ieee754;
+
+use crate::components::copytoclipboard::CopyToClipboard;
+
+use self::ieee754::{Decimal, Decoded, BINARY32};
+use yew::prelude::*;
+
+pub struct FloatInspector {
+    link: ComponentLink<Self>,
+    input: String,
+    binary32: Option<Decoded>,
+    binary64: Option<Decoded>,
+    reference: Option<Decimal>,
+}
+
+pub enum FloatInspectorMsg {
+    Inspect(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+}
+
+impl Component for FloatInspector {
+    type Message = FloatInspectorMsg;
+    type Properties = Props;
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        FloatInspector {
+            link,
+            input: "".to_string(),
+            binary32: None,
+            binary64: None,
+            reference: None,
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            FloatInspectorMsg::Inspect(value) => {
+                self.binary32 = None;
+                self.binary64 = None;
+                self.reference = None;
+
+                if let Some(decoded) = ieee754::parse_pattern(&value) {
+                    // the bit pattern is the reference, the other format shows how it converts
+                    if decoded.format == BINARY32 {
+                        let float = f32::from_bits(decoded.bits as u32);
+                        self.binary32 = Some(decoded);
+                        self.binary64 = Some(Decoded::from_f64(float as f64));
+                    } else {
+                        let float = f64::from_bits(decoded.bits);
+                        self.binary32 = Some(Decoded::from_f32(float as f32));
+                        self.binary64 = Some(decoded);
+                    }
+                    self.reference = decoded.exact();
+                } else if let (Ok(float32), Ok(float64)) = (value.trim().parse::<f32>(), value.trim().parse::<f64>()) {
+                    self.binary32 = Some(Decoded::from_f32(float32));
+                    self.binary64 = Some(Decoded::from_f64(float64));
+                    // a value beyond the formats, like 1e999, has no finite rounding error
+                    if float64.is_finite() {
+                        self.reference = Decimal::parse(&value);
+                    }
+                }
+
+                self.input = value;
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <form class="uk-grid-small uk-form-stacked" uk-grid="">
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="float">{ "Float or Bit Pattern" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="float" />
+                            <input class="uk-input uk-form-large"
+                                   id="float"
+                                   type="text"
+                                   placeholder="0.1, -inf, NaN, 0x3DCCCCCD, or 0b0011..."
+                                   oninput=self.link.callback(|d: InputData| FloatInspectorMsg::Inspect(d.value))
+                                   value=self.input.clone() />
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-1-2"></div>
+                { self.view_format("binary32 (f32)", "f32", self.binary32.as_ref()) }
+                { self.view_format("binary64 (f64)", "f64", self.binary64.as_ref()) }
+            </form>
+        }
+    }
+}
+
+impl FloatInspector {
+    /// Creates the column of one format's fields
+    fn view_format(&self, title: &str, id: &str, decoded: Option<&Decoded>) -> Html {
+        let field = |f: &dyn Fn(&Decoded) -> String| decoded.map(f).unwrap_or_default();
+        let exact = decoded.and_then(|d| d.exact());
+        let error = match (&exact, &self.reference) {
+            (Some(exact), Some(reference)) => {
+                let error = exact.sub(reference);
+                if error.is_zero() { "0 (exact)".to_string() } else { error.to_scientific(6) }
+            }
+            _ => "".to_string(),
+        };
+
+        html! {
+            <div class="uk-width-1-2">
+                <h4 class="uk-margin-small">{ title }</h4>
+                { self.output(&format!("{}hex", id), "Hex", field(&|d| d.hex())) }
+                { self.output(&format!("{}bin", id), "Sign Exponent Mantissa", field(&|d| d.binary())) }
+                { self.output(&format!("{}fields", id), "Fields", field(&|d| format!(
+                    "sign {}, exponent {} (unbiased {}), mantissa 0x{:X}",
+                    d.sign as u8, d.exponent, d.unbiased_exponent(), d.mantissa))) }
+                { self.output(&format!("{}class", id), "Class", field(&|d| d.class().to_string())) }
+                { self.output(&format!("{}exact", id), "Exact Value", exact.map(|e| e.to_string()).unwrap_or_default()) }
+                { self.output(&format!("{}error", id), "Rounding Error", error) }
+            </div>
+        }
+    }
+
+    /// Creates a read-only field with a copy to clipboard button
+    fn output(&self, id: &str, text: &str, value: String) -> Html {
+        html! {
+            <div class="uk-margin-small">
+                <label class="uk-form-label" for=id.to_string()>{ text }</label>
+                <div class="uk-form-controls">
+                    <div class="uk-inline uk-width-expand">
+                        <CopyToClipboard from=id.to_string() />
+                        <input class="uk-input"
+                               id=id.to_string()
+                               type="text"
+                               value=value
+                               disabled=true />
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
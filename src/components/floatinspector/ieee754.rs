@@ -0,0 +1,297 @@
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The layout of an IEEE 754 binary interchange format
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Format {
+    pub exponent_bits: u32,
+    pub mantissa_bits: u32,
+}
+
+pub const BINARY32: Format = Format { exponent_bits: 8, mantissa_bits: 23 };
+pub const BINARY64: Format = Format { exponent_bits: 11, mantissa_bits: 52 };
+
+/// The largest decimal exponent read exactly, beyond the range of binary64 with room for long mantissas
+const MAX_EXPONENT: i64 = 400;
+
+impl Format {
+    pub fn width(&self) -> u32 {
+        1 + self.exponent_bits + self.mantissa_bits
+    }
+
+    pub fn bias(&self) -> i64 {
+        (1 << (self.exponent_bits - 1)) - 1
+    }
+
+    fn max_exponent(&self) -> u64 {
+        (1 << self.exponent_bits) - 1
+    }
+}
+
+/// The kind of value a bit pattern encodes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Class {
+    Zero,
+    Subnormal,
+    Normal,
+    Infinity,
+    Nan { quiet: bool, payload: u64 },
+}
+
+impl fmt::Display for Class {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Class::Zero => write!(f, "zero"),
+            Class::Subnormal => write!(f, "subnormal"),
+            Class::Normal => write!(f, "normal"),
+            Class::Infinity => write!(f, "infinity"),
+            Class::Nan { quiet: true, payload } => write!(f, "quiet NaN, payload 0x{:X}", payload),
+            Class::Nan { quiet: false, payload } => write!(f, "signaling NaN, payload 0x{:X}", payload),
+        }
+    }
+}
+
+/// A bit pattern split into its sign, biased exponent, and mantissa fields
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decoded {
+    pub format: Format,
+    pub bits: u64,
+    pub sign: bool,
+    pub exponent: u64,
+    pub mantissa: u64,
+}
+
+impl Decoded {
+    pub fn new(format: Format, bits: u64) -> Self {
+        Decoded {
+            format,
+            bits,
+            sign: bits >> (format.width() - 1) & 1 == 1,
+            exponent: bits >> format.mantissa_bits & format.max_exponent(),
+            mantissa: bits & ((1 << format.mantissa_bits) - 1),
+        }
+    }
+
+    pub fn from_f32(value: f32) -> Self {
+        Decoded::new(BINARY32, value.to_bits() as u64)
+    }
+
+    pub fn from_f64(value: f64) -> Self {
+        Decoded::new(BINARY64, value.to_bits())
+    }
+
+    pub fn class(&self) -> Class {
+        match (self.exponent, self.mantissa) {
+            (0, 0) => Class::Zero,
+            (0, _) => Class::Subnormal,
+            (e, 0) if e == self.format.max_exponent() => Class::Infinity,
+            (e, m) if e == self.format.max_exponent() => {
+                let quiet_bit = 1 << (self.format.mantissa_bits - 1);
+                Class::Nan { quiet: m & quiet_bit != 0, payload: m & !quiet_bit }
+            }
+            _ => Class::Normal,
+        }
+    }
+
+    /// Returns the exponent without bias, subnormals share the exponent of the smallest normal
+    pub fn unbiased_exponent(&self) -> i64 {
+        self.exponent.max(1) as i64 - self.format.bias()
+    }
+
+    /// Formats the bit pattern in hex zero-padded to the format's width
+    pub fn hex(&self) -> String {
+        format!("{:01$X}", self.bits, (self.format.width() / 4) as usize)
+    }
+
+    /// Formats the bit pattern as sign, exponent, and mantissa fields separated by spaces
+    pub fn binary(&self) -> String {
+        format!("{} {:0ew$b} {:0mw$b}",
+                self.sign as u8,
+                self.exponent,
+                self.mantissa,
+                ew = self.format.exponent_bits as usize,
+                mw = self.format.mantissa_bits as usize)
+    }
+
+    /// Returns the exact decimal value the bit pattern stores or none for infinities and NaNs
+    pub fn exact(&self) -> Option<Decimal> {
+        let significand = match self.class() {
+            Class::Zero | Class::Subnormal => self.mantissa,
+            Class::Normal => self.mantissa | 1 << self.format.mantissa_bits,
+            Class::Infinity | Class::Nan { .. } => return None,
+        };
+
+        let exponent = self.unbiased_exponent() - self.format.mantissa_bits as i64;
+        let mut digits = BigInt::from(significand);
+        let mut scale = 0;
+
+        // m * 2^-n equals m * 5^n / 10^n
+        if exponent >= 0 {
+            digits <<= exponent as usize;
+        } else {
+            scale = (-exponent) as u32;
+            digits *= BigInt::from(5).pow(scale);
+        }
+
+        if self.sign {
+            digits = -digits;
+        }
+
+        Some(Decimal::new(digits, scale))
+    }
+}
+
+/// An exact decimal number stored as digits divided by 10^scale
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decimal {
+    digits: BigInt,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn new(digits: BigInt, scale: u32) -> Self {
+        let mut decimal = Decimal { digits, scale };
+        let ten = BigInt::from(10);
+        while decimal.scale > 0 && (&decimal.digits % &ten).is_zero() {
+            decimal.digits /= &ten;
+            decimal.scale -= 1;
+        }
+        decimal
+    }
+
+    /// Parses a decimal number like `-1.25e-3` exactly
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(pos) => (&s[..pos], s[pos + 1..].parse::<i64>().ok()?),
+            None => (s, 0),
+        };
+        if exponent.unsigned_abs() > MAX_EXPONENT as u64 {
+            return None;
+        }
+
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let (negative, integer) = match integer.strip_prefix('-') {
+            Some(integer) => (true, integer),
+            None => (false, integer.strip_prefix('+').unwrap_or(integer)),
+        };
+
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+
+        if !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut digits = format!("{}{}", integer, fraction).parse::<BigInt>().ok()?;
+        let mut scale = fraction.len() as i64 - exponent;
+        if scale < 0 {
+            digits *= BigInt::from(10).pow(u32::try_from(-scale).ok()?);
+            scale = 0;
+        }
+
+        if negative {
+            digits = -digits;
+        }
+
+        Some(Decimal::new(digits, u32::try_from(scale).ok()?))
+    }
+
+    pub fn sub(&self, other: &Decimal) -> Decimal {
+        let scale = self.scale.max(other.scale);
+        let ten = BigInt::from(10);
+        let a = &self.digits * ten.pow(scale - self.scale);
+        let b = &other.digits * ten.pow(scale - other.scale);
+        Decimal::new(a - b, scale)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_zero()
+    }
+
+    /// Formats the number in scientific notation rounded to the given significant digits
+    pub fn to_scientific(&self, significant: usize) -> String {
+        if self.digits.is_zero() {
+            return "0".to_string();
+        }
+
+        let digits = self.digits.abs().to_string();
+        let exponent = digits.len() as i64 - 1 - self.scale as i64;
+        let mut mantissa: Vec<u8> = digits.bytes().take(significant).map(|b| b - b'0').collect();
+        let mut exponent = exponent;
+
+        // round half up on the first dropped digit
+        if digits.len() > significant && digits.as_bytes()[significant] >= b'5' {
+            let mut i = mantissa.len();
+            loop {
+                if i == 0 {
+                    mantissa.insert(0, 1);
+                    mantissa.pop();
+                    exponent += 1;
+                    break;
+                }
+                i -= 1;
+                if mantissa[i] == 9 {
+                    mantissa[i] = 0;
+                } else {
+                    mantissa[i] += 1;
+                    break;
+                }
+            }
+        }
+
+        while mantissa.len() > 1 && mantissa.last() == Some(&0) {
+            mantissa.pop();
+        }
+
+        let sign = if self.digits.is_negative() { "-" } else { "" };
+        let mut out = format!("{}{}", sign, mantissa[0]);
+        if mantissa.len() > 1 {
+            out.push('.');
+            out.extend(mantissa[1..].iter().map(|d| (b'0' + d) as char));
+        }
+        format!("{}e{}", out, exponent)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.digits.is_negative() { "-" } else { "" };
+        let digits = self.digits.abs().to_string();
+        let scale = self.scale as usize;
+
+        match digits.len().cmp(&scale) {
+            Ordering::Greater if scale > 0 => {
+                let (integer, fraction) = digits.split_at(digits.len() - scale);
+                write!(f, "{}{}.{}", sign, integer, fraction)
+            }
+            Ordering::Greater => write!(f, "{}{}", sign, digits),
+            _ => write!(f, "{}0.{:0>2$}", sign, digits, scale),
+        }
+    }
+}
+
+/// Parses a raw bit pattern like `0x3F800000` or `0b0011...` into the format matching its length
+pub fn parse_pattern(s: &str) -> Option<Decoded> {
+    let s: String = s.trim().chars().filter(|c| *c != '_' && !c.is_whitespace()).collect();
+
+    let (digits, radix, bits_per_digit) = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        (hex, 16, 4)
+    } else if let Some(bin) = s.strip_prefix("0b").or_else(|| s.strip_prefix("0B")) {
+        (bin, 2, 1)
+    } else {
+        return None;
+    };
+
+    let bits = digits.len() as u32 * bits_per_digit;
+    let format = match bits {
+        1..=32 => BINARY32,
+        33..=64 => BINARY64,
+        _ => return None,
+    };
+
+    u64::from_str_radix(digits, radix).ok().map(|bits| Decoded::new(format, bits))
+}
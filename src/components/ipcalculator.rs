@@ -1,26 +1,88 @@
+mod classify;
+mod ipv6;
+mod lookup;
+mod planner;
+mod subnet;
+mod summarize;
+
 use crate::components::copytoclipboard::CopyToClipboard;
 
-use std::net::Ipv4Addr;
+use self::ipv6::Ipv6Subnet;
+use self::lookup::LookupEndpoints;
+use self::planner::{Allocation, ExportFormat};
+use self::subnet::Ipv4Subnet;
+use self::summarize::Ipv4Set;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use wasm_bindgen::prelude::*;
+use yew::format::{Json, Nothing, Text};
 use yew::prelude::*;
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::services::storage::{Area, StorageService};
+
+const LOOKUP_ENDPOINTS_KEY: &str = "lookup_endpoints";
 
 #[wasm_bindgen(module = "/toolbox.js")]
 extern "C" {
-    fn getMyIP() -> String;
+    fn getLocalIPs(callback: JsValue);
 }
 
 pub struct IpCalculator {
     link: ComponentLink<Self>,
+    storage: Result<StorageService, &'static str>,
+    mode: IpCalculatorMode,
     ipv4: String,
     integer: String,
     bits: String,
+    endpoints: LookupEndpoints,
+    lookup_tasks: Vec<FetchTask>,
+    pending_lookups: usize,
+    lookup_errors: Vec<String>,
+    my_ipv6: String,
+    local_ips: String,
+    subnet: String,
+    parsed_subnet: Option<Ipv4Subnet>,
+    ipv6: String,
+    ipv6_integer: String,
+    parsed_ipv6: Option<Ipv6Subnet>,
+    planner_parent: String,
+    planner_input: String,
+    planner_format: ExportFormat,
+    plan: Vec<Allocation>,
+    plan_error: String,
+    set_a: String,
+    set_b: String,
+    parsed_set_a: Ipv4Set,
+    parsed_set_b: Ipv4Set,
+    set_error: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IpCalculatorMode {
+    Converter,
+    Subnet,
+    Ipv6,
+    Planner,
+    Summarize,
 }
 
 pub enum IpCalculatorMsg {
+    ChangeMode(IpCalculatorMode),
     ConvertBits(String),
     ConvertInteger(String),
     ConvertIpv4(String),
+    CalculateSubnet(String),
+    ConvertIpv6(String),
+    ConvertIpv6Integer(String),
+    ChangePlannerParent(String),
+    ChangePlannerInput(String),
+    ChangePlannerFormat(ExportFormat),
+    ChangeSetA(String),
+    ChangeSetB(String),
     MyIP,
+    ReceiveMyIP(Result<IpAddr, String>),
+    ReceiveLocalIPs(String),
+    ChangeIpv4Endpoint(String),
+    ChangeIpv6Endpoint(String),
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -32,11 +94,46 @@ impl Component for IpCalculator {
     type Properties = Props;
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let storage = StorageService::new(Area::Local);
+        let endpoints = match &storage {
+            Ok(storage) => {
+                if let Json(Ok(endpoints)) = storage.restore(LOOKUP_ENDPOINTS_KEY) {
+                    endpoints
+                } else {
+                    LookupEndpoints::default()
+                }
+            },
+            Err(_) => LookupEndpoints::default(),
+        };
+
         IpCalculator {
             link: link,
+            storage,
+            mode: IpCalculatorMode::Converter,
             ipv4: "".to_string(),
             integer: "".to_string(),
             bits: "".to_string(),
+            endpoints,
+            lookup_tasks: Vec::new(),
+            pending_lookups: 0,
+            lookup_errors: Vec::new(),
+            my_ipv6: "".to_string(),
+            local_ips: "".to_string(),
+            subnet: "".to_string(),
+            parsed_subnet: None,
+            ipv6: "".to_string(),
+            ipv6_integer: "".to_string(),
+            parsed_ipv6: None,
+            planner_parent: "".to_string(),
+            planner_input: "".to_string(),
+            planner_format: ExportFormat::Csv,
+            plan: Vec::new(),
+            plan_error: "".to_string(),
+            set_a: "".to_string(),
+            set_b: "".to_string(),
+            parsed_set_a: Ipv4Set::default(),
+            parsed_set_b: Ipv4Set::default(),
+            set_error: "".to_string(),
         }
     }
 
@@ -46,6 +143,9 @@ impl Component for IpCalculator {
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
+            IpCalculatorMsg::ChangeMode(mode) => {
+                self.mode = mode;
+            }
             IpCalculatorMsg::ConvertBits(value) => {
                 let bytes: Vec<&str> = value.split(".").collect();
 
@@ -105,29 +205,123 @@ impl Component for IpCalculator {
 
                 self.ipv4 = value;
             }
+            IpCalculatorMsg::CalculateSubnet(value) => {
+                self.parsed_subnet = value.parse::<Ipv4Subnet>().ok();
+                self.subnet = value;
+            }
+            IpCalculatorMsg::ConvertIpv6(value) => {
+                self.parsed_ipv6 = value.parse::<Ipv6Subnet>().ok();
+                match self.parsed_ipv6 {
+                    Some(subnet) => self.ipv6_integer = u128::from(subnet.addr()).to_string(),
+                    None => self.ipv6_integer.clear(),
+                }
+
+                self.ipv6 = value;
+            }
+            IpCalculatorMsg::ConvertIpv6Integer(value) => {
+                match value.parse::<u128>() {
+                    Ok(integer) => {
+                        let addr = Ipv6Addr::from(integer);
+                        self.ipv6 = addr.to_string();
+                        self.parsed_ipv6 = self.ipv6.parse::<Ipv6Subnet>().ok();
+                    }
+                    Err(_) => {
+                        self.ipv6.clear();
+                        self.parsed_ipv6 = None;
+                    }
+                }
+
+                self.ipv6_integer = value;
+            }
+            IpCalculatorMsg::ChangePlannerParent(value) => {
+                self.planner_parent = value;
+                self.calc_plan();
+            }
+            IpCalculatorMsg::ChangePlannerInput(value) => {
+                self.planner_input = value;
+                self.calc_plan();
+            }
+            IpCalculatorMsg::ChangePlannerFormat(format) => {
+                self.planner_format = format;
+            }
+            IpCalculatorMsg::ChangeSetA(value) => {
+                self.set_a = value;
+                self.calc_sets();
+            }
+            IpCalculatorMsg::ChangeSetB(value) => {
+                self.set_b = value;
+                self.calc_sets();
+            }
             IpCalculatorMsg::MyIP => {
-                let myip = getMyIP();
-                match myip.parse::<Ipv4Addr>() {
-                    Ok(addr) => { 
-                        self.ipv4 = myip;
-                        self.integer = (((addr.octets()[0] as u32) << 24) + 
-                                        ((addr.octets()[1] as u32) << 16) + 
-                                        ((addr.octets()[2] as u32) <<  8) + 
-                                        ((addr.octets()[3] as u32))).to_string();
-                        self.bits = format!("{:08b}.{:08b}.{:08b}.{:08b}", 
-                                            addr.octets()[0] as u32, 
-                                            addr.octets()[1] as u32,
-                                            addr.octets()[2] as u32,
-                                            addr.octets()[3] as u32);
+                self.lookup_tasks.clear();
+                self.lookup_errors.clear();
+                self.my_ipv6.clear();
+                self.local_ips.clear();
+
+                let endpoints = [self.endpoints.ipv4.clone(), self.endpoints.ipv6.clone()];
+                for endpoint in endpoints.iter().filter(|endpoint| !endpoint.trim().is_empty()) {
+                    match self.fetch_my_ip(endpoint.trim()) {
+                        Ok(task) => self.lookup_tasks.push(task),
+                        Err(e) => self.lookup_errors.push(e),
                     }
-                    Err(_) => self.clear()
                 }
+
+                let callback = self.link.callback(IpCalculatorMsg::ReceiveLocalIPs);
+                getLocalIPs(Closure::once_into_js(move |ips: String| callback.emit(ips)));
+                self.pending_lookups = self.lookup_tasks.len() + 1;
+            }
+            IpCalculatorMsg::ReceiveMyIP(result) => {
+                match result {
+                    Ok(IpAddr::V4(addr)) => self.set_ipv4(addr),
+                    Ok(IpAddr::V6(addr)) => self.my_ipv6 = addr.to_string(),
+                    Err(e) => self.lookup_errors.push(e),
+                }
+                self.finish_lookup();
+            }
+            IpCalculatorMsg::ReceiveLocalIPs(ips) => {
+                self.local_ips = ips;
+                self.finish_lookup();
+            }
+            IpCalculatorMsg::ChangeIpv4Endpoint(value) => {
+                self.endpoints.ipv4 = value;
+                self.store_endpoints();
+            }
+            IpCalculatorMsg::ChangeIpv6Endpoint(value) => {
+                self.endpoints.ipv6 = value;
+                self.store_endpoints();
             }
         }
         true
     }
 
     fn view(&self) -> Html {
+        html! {
+            <>
+                <ul class="uk-subnav uk-subnav-pill">
+                    { self.mode_tab("Converter", IpCalculatorMode::Converter) }
+                    { self.mode_tab("Subnet", IpCalculatorMode::Subnet) }
+                    { self.mode_tab("IPv6", IpCalculatorMode::Ipv6) }
+                    { self.mode_tab("Planner", IpCalculatorMode::Planner) }
+                    { self.mode_tab("Summarize", IpCalculatorMode::Summarize) }
+                </ul>
+                {
+                    match self.mode {
+                        IpCalculatorMode::Converter => self.view_converter(),
+                        IpCalculatorMode::Subnet => self.view_subnet(),
+                        IpCalculatorMode::Ipv6 => self.view_ipv6(),
+                        IpCalculatorMode::Planner => self.view_planner(),
+                        IpCalculatorMode::Summarize => self.view_summarize(),
+                    }
+                }
+            </>
+        }
+    }
+}
+
+impl IpCalculator {
+    fn view_converter(&self) -> Html {
+        let addr = self.ipv4.parse::<Ipv4Addr>().ok();
+
         html! {
             <form class="uk-grid-small uk-form-stacked" uk-grid="">
                 <div class="uk-width-1-4">
@@ -177,18 +371,397 @@ impl Component for IpCalculator {
                                     type="button" 
                                     id="myip"
                                     uk-tooltip="use my IP address"
+                                    disabled={ self.pending_lookups > 0 }
                                     onclick=self.link.callback(|_| IpCalculatorMsg::MyIP)>
-                                {"My IP"}
+                                {
+                                    if self.pending_lookups > 0 {
+                                        html! { <div uk-spinner="ratio: 0.6"></div> }
+                                    } else {
+                                        html! { { "My IP" } }
+                                    }
+                                }
                             </button>
+                            <button class="uk-button uk-button-link uk-margin-small-left"
+                                    type="button"
+                                    uk-icon="icon: cog"
+                                    uk-tooltip="lookup endpoints">
+                            </button>
+                            <div uk-dropdown="mode: click" class="uk-width-large">
+                                <label class="uk-form-label" for="ipv4endpoint">{ "IPv4 Endpoint" }</label>
+                                <input class="uk-input uk-form-small"
+                                       id="ipv4endpoint"
+                                       type="text"
+                                       oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangeIpv4Endpoint(d.value))
+                                       value=self.endpoints.ipv4.clone() />
+                                <label class="uk-form-label" for="ipv6endpoint">{ "IPv6 Endpoint" }</label>
+                                <input class="uk-input uk-form-small"
+                                       id="ipv6endpoint"
+                                       type="text"
+                                       oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangeIpv6Endpoint(d.value))
+                                       value=self.endpoints.ipv6.clone() />
+                            </div>
                         </div>
                     </div>
                 </div>
+                { self.output("uk-width-1-3", "ipv4type", "Type", addr.map(|a| classify::classify_ipv4(&a).to_string()).unwrap_or_default()) }
+                { self.output("uk-width-1-6", "ipv4class", "Class", addr.map(|a| classify::legacy_class(&a).to_string()).unwrap_or_default()) }
+                { self.output("uk-width-1-2", "myipv6", "My IPv6", self.my_ipv6.clone()) }
+                { self.output("uk-width-1-1", "localips", "Local Addresses", self.local_ips.clone()) }
+                <div class="uk-width-1-1 uk-text-danger">
+                    { for self.lookup_errors.iter().map(|e| html! { <div>{ e }</div> }) }
+                </div>
             </form>
         }
     }
-}
 
-impl IpCalculator {
+    fn view_subnet(&self) -> Html {
+        let subnet = self.parsed_subnet;
+        let field = |f: fn(&Ipv4Subnet) -> String| subnet.as_ref().map(f).unwrap_or_default();
+
+        html! {
+            <form class="uk-grid-small uk-form-stacked" uk-grid="">
+                <div class="uk-width-1-3">
+                    <label class="uk-form-label" for="subnet">{ "Network" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="subnet" />
+                            <input class="uk-input uk-form-large"
+                                   id="subnet"
+                                   type="text"
+                                   placeholder="10.20.0.0/19 or 10.20.0.0 255.255.224.0"
+                                   oninput=self.link.callback(|d: InputData| IpCalculatorMsg::CalculateSubnet(d.value))
+                                   value=self.subnet.clone() />
+                        </div>
+                    </div>
+                </div>
+                { self.output("uk-width-1-3", "cidr", "CIDR", field(|s| format!("{}/{}", s.network(), s.prefix()))) }
+                { self.output("uk-width-1-3", "hostcount", "Hosts", field(|s| s.host_count().to_string())) }
+                { self.output("uk-width-1-4", "network", "Network Address", field(|s| s.network().to_string())) }
+                { self.output("uk-width-1-4", "broadcast", "Broadcast Address", field(|s| s.broadcast().to_string())) }
+                { self.output("uk-width-1-4", "netmask", "Netmask", field(|s| s.netmask().to_string())) }
+                { self.output("uk-width-1-4", "wildcard", "Wildcard Mask", field(|s| s.wildcard().to_string())) }
+                { self.output("uk-width-1-4", "firsthost", "First Host", field(|s| s.first_host().to_string())) }
+                { self.output("uk-width-1-4", "lasthost", "Last Host", field(|s| s.last_host().to_string())) }
+                { self.output("uk-width-1-2", "subnettype", "Type", field(|s| classify::classify_ipv4(&s.network()).to_string())) }
+            </form>
+        }
+    }
+
+    fn view_ipv6(&self) -> Html {
+        let ipv6 = self.parsed_ipv6;
+        let field = |f: fn(&Ipv6Subnet) -> Option<String>| ipv6.as_ref().and_then(f).unwrap_or_default();
+
+        html! {
+            <form class="uk-grid-small uk-form-stacked" uk-grid="">
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="ipv6">{ "IPv6" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="ipv6" />
+                            <input class="uk-input uk-form-large"
+                                   id="ipv6"
+                                   type="text"
+                                   placeholder="2001:db8::1/64"
+                                   oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ConvertIpv6(d.value))
+                                   value=self.ipv6.clone() />
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="ipv6integer">{ "Integer" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="ipv6integer" />
+                            <input class="uk-input uk-form-large"
+                                   id="ipv6integer"
+                                   type="text"
+                                   oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ConvertIpv6Integer(d.value))
+                                   value=self.ipv6_integer.clone() />
+                        </div>
+                    </div>
+                </div>
+                { self.output("uk-width-1-2", "ipv6compressed", "Compressed", field(|s| Some(s.addr().to_string()))) }
+                { self.output("uk-width-1-2", "ipv6expanded", "Expanded", field(|s| Some(ipv6::expanded(&s.addr())))) }
+                { self.output("uk-width-1-1", "ipv6bits", "Bits", field(|s| Some(ipv6::bits(&s.addr())))) }
+                { self.output("uk-width-1-3", "ipv6type", "Type", field(|s| Some(classify::classify_ipv6(&s.addr()).to_string()))) }
+                { self.output("uk-width-1-3", "ipv6network", "Network", field(|s| Some(format!("{}/{}", s.network()?, s.prefix()?)))) }
+                { self.output("uk-width-1-3", "ipv6last", "Last Address", field(|s| s.last().map(|l| l.to_string()))) }
+            </form>
+        }
+    }
+
+    fn view_planner(&self) -> Html {
+        let format_button = |text: &str, format: ExportFormat| {
+            let class = if self.planner_format == format { "uk-button uk-button-primary" } else { "uk-button uk-button-default" };
+            html! {
+                <button class=class
+                        type="button"
+                        onclick=self.link.callback(move |_| IpCalculatorMsg::ChangePlannerFormat(format))>
+                    { text }
+                </button>
+            }
+        };
+
+        html! {
+            <form class="uk-grid-small uk-form-stacked" uk-grid="">
+                <div class="uk-width-1-3">
+                    <label class="uk-form-label" for="plannerparent">{ "Parent Block" }</label>
+                    <div class="uk-form-controls">
+                        <input class="uk-input uk-form-large"
+                               id="plannerparent"
+                               type="text"
+                               placeholder="172.16.0.0/16"
+                               oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangePlannerParent(d.value))
+                               value=self.planner_parent.clone() />
+                    </div>
+                    <label class="uk-form-label uk-margin-small-top" for="plannerinput">{ "Prefix Length or Requirements" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea"
+                                  id="plannerinput"
+                                  rows="6"
+                                  placeholder="/24\n\nor one requirement per line:\nweb 500\ndb 60"
+                                  oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangePlannerInput(d.value))
+                                  value=self.planner_input.clone() />
+                    </div>
+                    <div class="uk-text-danger uk-margin-small-top">{ self.plan_error.clone() }</div>
+                </div>
+                <div class="uk-width-2-3">
+                    <label class="uk-form-label" for="plannerexport">{ "Export" }</label>
+                    <div class="uk-button-group uk-margin-small-bottom">
+                        { format_button("CSV", ExportFormat::Csv) }
+                        { format_button("Markdown", ExportFormat::Markdown) }
+                    </div>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="plannerexport" />
+                            <textarea class="uk-textarea"
+                                      id="plannerexport"
+                                      rows="7"
+                                      readonly=true
+                                      value=planner::export(&self.plan, self.planner_format) />
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-1-1">
+                    <table class="uk-table uk-table-small uk-table-divider">
+                        <thead>
+                            <tr>
+                                <th>{ "Name" }</th>
+                                <th>{ "Requested" }</th>
+                                <th>{ "Subnet" }</th>
+                                <th>{ "First Host" }</th>
+                                <th>{ "Last Host" }</th>
+                                <th>{ "Broadcast" }</th>
+                                <th>{ "Hosts" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for self.plan.iter().map(|allocation| self.view_allocation(allocation)) }
+                        </tbody>
+                    </table>
+                </div>
+            </form>
+        }
+    }
+
+    /// Creates a table row of a planned child subnet
+    fn view_allocation(&self, allocation: &Allocation) -> Html {
+        let requested = allocation.requested.map(|r| r.to_string()).unwrap_or_default();
+
+        match &allocation.subnet {
+            Some(subnet) => html! {
+                <tr>
+                    <td>{ allocation.name.clone() }</td>
+                    <td>{ requested }</td>
+                    <td>{ subnet.to_string() }</td>
+                    <td>{ subnet.first_host().to_string() }</td>
+                    <td>{ subnet.last_host().to_string() }</td>
+                    <td>{ subnet.broadcast().to_string() }</td>
+                    <td>{ subnet.host_count().to_string() }</td>
+                </tr>
+            },
+            None => html! {
+                <tr class="uk-text-danger">
+                    <td>{ allocation.name.clone() }</td>
+                    <td>{ requested }</td>
+                    <td colspan="5">{ "does not fit into the parent block" }</td>
+                </tr>
+            },
+        }
+    }
+
+    fn view_summarize(&self) -> Html {
+        let (a, b) = (&self.parsed_set_a, &self.parsed_set_b);
+        let cidrs = |set: Ipv4Set| summarize::format_cidrs(&set.to_cidrs());
+        let with_b = |f: fn(&Ipv4Set, &Ipv4Set) -> Ipv4Set| if b.is_empty() { "".to_string() } else { cidrs(f(a, b)) };
+
+        html! {
+            <form class="uk-grid-small uk-form-stacked" uk-grid="">
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="seta">{ "Set A" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea"
+                                  id="seta"
+                                  rows="6"
+                                  placeholder="10.0.0.5 - 10.0.0.90\n192.168.0.0/24, 192.168.1.7"
+                                  oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangeSetA(d.value))
+                                  value=self.set_a.clone() />
+                    </div>
+                </div>
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="setb">{ "Set B" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea"
+                                  id="setb"
+                                  rows="6"
+                                  placeholder="optional, for union, intersection, and difference"
+                                  oninput=self.link.callback(|d: InputData| IpCalculatorMsg::ChangeSetB(d.value))
+                                  value=self.set_b.clone() />
+                    </div>
+                </div>
+                <div class="uk-width-1-1 uk-text-danger">{ self.set_error.clone() }</div>
+                { self.output_area("uk-width-1-4", "summarya", "Summary A", cidrs(a.clone())) }
+                { self.output_area("uk-width-1-4", "union", "Union A \u{222a} B", with_b(Ipv4Set::union)) }
+                { self.output_area("uk-width-1-4", "intersection", "Intersection A \u{2229} B", with_b(Ipv4Set::intersection)) }
+                { self.output_area("uk-width-1-4", "difference", "Difference A \u{2216} B", with_b(Ipv4Set::difference)) }
+            </form>
+        }
+    }
+
+    /// Creates a tab of the mode selection
+    fn mode_tab(&self, text: &str, mode: IpCalculatorMode) -> Html {
+        let class = if self.mode == mode { "uk-active" } else { "" };
+        let onclick = self.link.callback(move |e: MouseEvent| {
+            e.prevent_default();
+            IpCalculatorMsg::ChangeMode(mode)
+        });
+
+        html! {
+            <li class=class>
+                <a href="#" onclick=onclick>{ text }</a>
+            </li>
+        }
+    }
+
+    /// Creates a read-only field with a copy to clipboard button
+    fn output(&self, width: &str, id: &str, text: &str, value: String) -> Html {
+        html! {
+            <div class=width.to_string()>
+                <label class="uk-form-label" for=id.to_string()>{ text }</label>
+                <div class="uk-form-controls">
+                    <div class="uk-inline uk-width-expand">
+                        <CopyToClipboard from=id.to_string() />
+                        <input class="uk-input uk-form-large"
+                               id=id.to_string()
+                               type="text"
+                               value=value
+                               disabled=true />
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Creates a read-only multi-line field with a copy to clipboard button
+    fn output_area(&self, width: &str, id: &str, text: &str, value: String) -> Html {
+        html! {
+            <div class=width.to_string()>
+                <label class="uk-form-label" for=id.to_string()>{ text }</label>
+                <div class="uk-form-controls">
+                    <div class="uk-inline uk-width-expand">
+                        <CopyToClipboard from=id.to_string() />
+                        <textarea class="uk-textarea"
+                                  id=id.to_string()
+                                  rows="8"
+                                  readonly=true
+                                  value=value />
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Parses both address sets of the summarize mode
+    fn calc_sets(&mut self) {
+        self.set_error.clear();
+
+        match (self.set_a.parse::<Ipv4Set>(), self.set_b.parse::<Ipv4Set>()) {
+            (Ok(a), Ok(b)) => {
+                self.parsed_set_a = a;
+                self.parsed_set_b = b;
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                self.parsed_set_a = Ipv4Set::default();
+                self.parsed_set_b = Ipv4Set::default();
+                self.set_error = e;
+            }
+        }
+    }
+
+    /// Lays out the child subnets from the planner's parent block and input
+    fn calc_plan(&mut self) {
+        self.plan.clear();
+        self.plan_error.clear();
+
+        if self.planner_parent.trim().is_empty() || self.planner_input.trim().is_empty() {
+            return;
+        }
+
+        let result = self.planner_parent
+            .parse::<Ipv4Subnet>()
+            .map_err(|e| e.to_string())
+            .and_then(|parent| planner::plan(&parent, &self.planner_input));
+
+        match result {
+            Ok(plan) => self.plan = plan,
+            Err(e) => self.plan_error = e,
+        }
+    }
+
+    /// Requests the user agent's public address from the given echo service
+    fn fetch_my_ip(&self, endpoint: &str) -> Result<FetchTask, String> {
+        let request = Request::get(endpoint)
+            .body(Nothing)
+            .map_err(|e| format!("{}: {}", endpoint, e))?;
+
+        let url = endpoint.to_string();
+        let callback = self.link.callback(move |response: Response<Text>| {
+            let (meta, body) = response.into_parts();
+            let result = match body {
+                Ok(body) if meta.status.is_success() => lookup::parse_response(&body),
+                Ok(_) => Err(format!("HTTP {}", meta.status)),
+                Err(e) => Err(e.to_string()),
+            };
+            IpCalculatorMsg::ReceiveMyIP(result.map_err(|e| format!("{}: {}", url, e)))
+        });
+
+        FetchService::fetch(request, callback).map_err(|e| format!("{}: {}", endpoint, e))
+    }
+
+    /// Counts down the pending lookups and drops the finished fetch tasks
+    fn finish_lookup(&mut self) {
+        self.pending_lookups = self.pending_lookups.saturating_sub(1);
+        if self.pending_lookups == 0 {
+            self.lookup_tasks.clear();
+        }
+    }
+
+    fn store_endpoints(&mut self) {
+        if let Ok(storage) = &mut self.storage {
+            storage.store(LOOKUP_ENDPOINTS_KEY, Json(&self.endpoints))
+        }
+    }
+
+    fn set_ipv4(&mut self, addr: Ipv4Addr) {
+        self.ipv4 = addr.to_string();
+        self.integer = u32::from(addr).to_string();
+        self.bits = format!("{:08b}.{:08b}.{:08b}.{:08b}",
+                            addr.octets()[0],
+                            addr.octets()[1],
+                            addr.octets()[2],
+                            addr.octets()[3]);
+    }
+
     fn clear(&mut self) {
         self.ipv4.clear();
         self.integer.clear();
@@ -0,0 +1,69 @@
+use super::{ipv6, subnet};
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Special-purpose IPv4 ranges as network, prefix length, and description, first match wins
+const IPV4_RANGES: &[([u8; 4], u8, &str)] = &[
+    ([0, 0, 0, 0], 8, "this network (RFC 791)"),
+    ([10, 0, 0, 0], 8, "private (RFC 1918)"),
+    ([100, 64, 0, 0], 10, "shared address space, CGNAT (RFC 6598)"),
+    ([127, 0, 0, 0], 8, "loopback (RFC 1122)"),
+    ([169, 254, 0, 0], 16, "link-local (RFC 3927)"),
+    ([172, 16, 0, 0], 12, "private (RFC 1918)"),
+    ([192, 0, 0, 0], 24, "reserved, IETF protocol assignments (RFC 6890)"),
+    ([192, 0, 2, 0], 24, "documentation, TEST-NET-1 (RFC 5737)"),
+    ([192, 168, 0, 0], 16, "private (RFC 1918)"),
+    ([198, 18, 0, 0], 15, "reserved, benchmarking (RFC 2544)"),
+    ([198, 51, 100, 0], 24, "documentation, TEST-NET-2 (RFC 5737)"),
+    ([203, 0, 113, 0], 24, "documentation, TEST-NET-3 (RFC 5737)"),
+    ([224, 0, 0, 0], 4, "multicast (RFC 5771)"),
+    ([255, 255, 255, 255], 32, "limited broadcast (RFC 919)"),
+    ([240, 0, 0, 0], 4, "reserved (RFC 1112)"),
+];
+
+/// Special-purpose IPv6 ranges as network, prefix length, and description, first match wins
+const IPV6_RANGES: &[([u16; 8], u8, &str)] = &[
+    ([0, 0, 0, 0, 0, 0, 0, 0], 128, "unspecified (RFC 4291)"),
+    ([0, 0, 0, 0, 0, 0, 0, 1], 128, "loopback (RFC 4291)"),
+    ([0, 0, 0, 0, 0, 0xffff, 0, 0], 96, "IPv4-mapped (RFC 4291)"),
+    ([0x64, 0xff9b, 0, 0, 0, 0, 0, 0], 96, "IPv4/IPv6 translation, NAT64 (RFC 6052)"),
+    ([0x100, 0, 0, 0, 0, 0, 0, 0], 64, "discard-only (RFC 6666)"),
+    ([0x2001, 0, 0, 0, 0, 0, 0, 0], 32, "Teredo tunneling (RFC 4380)"),
+    ([0x2001, 0xdb8, 0, 0, 0, 0, 0, 0], 32, "documentation (RFC 3849)"),
+    ([0x2002, 0, 0, 0, 0, 0, 0, 0], 16, "6to4 (RFC 3056)"),
+    ([0x2000, 0, 0, 0, 0, 0, 0, 0], 3, "global unicast (RFC 4291)"),
+    ([0xfc00, 0, 0, 0, 0, 0, 0, 0], 7, "unique local, private (RFC 4193)"),
+    ([0xfe80, 0, 0, 0, 0, 0, 0, 0], 10, "link-local (RFC 4291)"),
+    ([0xff00, 0, 0, 0, 0, 0, 0, 0], 8, "multicast (RFC 4291)"),
+];
+
+/// Returns a description of the special-purpose range the address belongs to or global unicast
+pub fn classify_ipv4(addr: &Ipv4Addr) -> &'static str {
+    let addr = u32::from(*addr);
+
+    IPV4_RANGES
+        .iter()
+        .find(|(network, prefix, _)| addr & subnet::prefix_to_mask(*prefix) == u32::from_be_bytes(*network))
+        .map_or("global unicast", |(_, _, description)| description)
+}
+
+/// Returns a description of the special-purpose range the address belongs to or reserved
+pub fn classify_ipv6(addr: &Ipv6Addr) -> &'static str {
+    let addr = u128::from(*addr);
+
+    IPV6_RANGES
+        .iter()
+        .find(|(network, prefix, _)| addr & ipv6::prefix_to_mask(*prefix) == u128::from(Ipv6Addr::from(*network)))
+        .map_or("reserved (RFC 4291)", |(_, _, description)| description)
+}
+
+/// Returns the legacy classful network class from A to E
+pub fn legacy_class(addr: &Ipv4Addr) -> &'static str {
+    match addr.octets()[0] {
+        0..=127 => "A",
+        128..=191 => "B",
+        192..=223 => "C",
+        224..=239 => "D (multicast)",
+        _ => "E (reserved)",
+    }
+}
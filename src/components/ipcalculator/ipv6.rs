@@ -0,0 +1,90 @@
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+/// An IPv6 address with an optional prefix length, e.g. `2001:db8::1/64`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ipv6Subnet {
+    addr: Ipv6Addr,
+    prefix: Option<u8>,
+}
+
+impl Ipv6Subnet {
+    pub fn addr(&self) -> Ipv6Addr {
+        self.addr
+    }
+
+    pub fn prefix(&self) -> Option<u8> {
+        self.prefix
+    }
+
+    /// Returns the network address if a prefix length was given
+    pub fn network(&self) -> Option<Ipv6Addr> {
+        self.prefix.map(|prefix| Ipv6Addr::from(u128::from(self.addr) & prefix_to_mask(prefix)))
+    }
+
+    /// Returns the last address of the network if a prefix length was given
+    pub fn last(&self) -> Option<Ipv6Addr> {
+        self.prefix.map(|prefix| Ipv6Addr::from(u128::from(self.addr) | !prefix_to_mask(prefix)))
+    }
+}
+
+impl fmt::Display for Ipv6Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.prefix {
+            Some(prefix) => write!(f, "{}/{}", self.addr, prefix),
+            None => write!(f, "{}", self.addr),
+        }
+    }
+}
+
+/// Parses compressed or expanded addresses like `2001:db8::1` with an optional `/len`
+impl FromStr for Ipv6Subnet {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr.trim(), Some(prefix.trim())),
+            None => (s, None),
+        };
+
+        let addr = addr.parse::<Ipv6Addr>().map_err(|_| "invalid IPv6 address")?;
+        let prefix = match prefix {
+            Some(prefix) => match prefix.parse::<u8>() {
+                Ok(prefix) if prefix <= 128 => Some(prefix),
+                _ => return Err("prefix length must be between 0 and 128"),
+            },
+            None => None,
+        };
+
+        Ok(Ipv6Subnet { addr, prefix })
+    }
+}
+
+/// Converts a prefix length to its 128 bit netmask
+pub fn prefix_to_mask(prefix: u8) -> u128 {
+    if prefix == 0 {
+        0
+    } else {
+        u128::MAX << (128 - prefix)
+    }
+}
+
+/// Formats the address with all eight hextets written out with leading zeros
+pub fn expanded(addr: &Ipv6Addr) -> String {
+    addr.segments()
+        .iter()
+        .map(|segment| format!("{:04x}", segment))
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+/// Formats the address as eight groups of 16 bits each
+pub fn bits(addr: &Ipv6Addr) -> String {
+    addr.segments()
+        .iter()
+        .map(|segment| format!("{:016b}", segment))
+        .collect::<Vec<String>>()
+        .join(":")
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// JSON keys under which common echo services return the address
+const ADDRESS_KEYS: &[&str] = &["ipAddress", "ip", "address", "query"];
+
+/// The echo services asked for the user agent's public addresses, stored in local storage
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LookupEndpoints {
+    pub ipv4: String,
+    pub ipv6: String,
+}
+
+impl Default for LookupEndpoints {
+    fn default() -> Self {
+        LookupEndpoints {
+            ipv4: "https://api.db-ip.com/v2/free/self".to_string(),
+            ipv6: "https://api6.ipify.org?format=json".to_string(),
+        }
+    }
+}
+
+/// Parses an echo service's response which is either the plain address or a JSON object like
+/// `{"ipAddress": "203.0.113.7"}`
+pub fn parse_response(body: &str) -> Result<IpAddr, String> {
+    let body = body.trim();
+    if let Ok(addr) = body.parse::<IpAddr>() {
+        return Ok(addr);
+    }
+
+    let json: serde_json::Value = serde_json::from_str(body).map_err(|_| "response is neither an address nor JSON".to_string())?;
+    ADDRESS_KEYS
+        .iter()
+        .find_map(|key| json.get(key).and_then(|value| value.as_str()))
+        .ok_or_else(|| "response contains no address".to_string())?
+        .parse::<IpAddr>()
+        .map_err(|_| "response contains an invalid address".to_string())
+}
@@ -0,0 +1,199 @@
+use super::subnet::Ipv4Subnet;
+
+use std::cmp::Reverse;
+use std::net::Ipv4Addr;
+
+/// Upper bound of child subnets to keep the rendered table reasonably small
+const MAX_SUBNETS: u64 = 1024;
+
+/// A named number of hosts a child subnet has to provide
+#[derive(Clone, Debug, PartialEq)]
+pub struct Requirement {
+    pub name: String,
+    pub hosts: u64,
+}
+
+/// A child subnet of the plan or none if the requirement did not fit into the parent block
+#[derive(Clone, Debug, PartialEq)]
+pub struct Allocation {
+    pub name: String,
+    pub requested: Option<u64>,
+    pub subnet: Option<Ipv4Subnet>,
+}
+
+/// The export formats of a plan
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+}
+
+/// Lays out the child subnets for the planner input which is either a target prefix length like `/24` or one
+/// requirement per line like `web 500`
+pub fn plan(parent: &Ipv4Subnet, input: &str) -> Result<Vec<Allocation>, String> {
+    let input = input.trim();
+
+    if let Some(prefix) = input.strip_prefix('/') {
+        let prefix = prefix.trim().parse::<u8>().map_err(|_| "invalid prefix length".to_string())?;
+        return split(parent, prefix);
+    }
+
+    Ok(allocate(parent, &parse_requirements(input)?))
+}
+
+/// Splits the parent block into equally sized child subnets of the given prefix length
+pub fn split(parent: &Ipv4Subnet, prefix: u8) -> Result<Vec<Allocation>, String> {
+    if prefix < parent.prefix() || prefix > 32 {
+        return Err(format!("prefix length must be between {} and 32", parent.prefix()));
+    }
+
+    let count = 1u64 << (prefix - parent.prefix());
+    if count > MAX_SUBNETS {
+        return Err(format!("splitting into {} subnets exceeds the limit of {}", count, MAX_SUBNETS));
+    }
+
+    let start = u32::from(parent.network()) as u64;
+    let size = 1u64 << (32 - prefix);
+
+    Ok((0..count)
+        .map(|i| Allocation {
+            name: format!("subnet {}", i + 1),
+            requested: None,
+            subnet: Ipv4Subnet::new(Ipv4Addr::from((start + i * size) as u32), prefix),
+        })
+        .collect())
+}
+
+/// Allocates the requirements largest first (VLSM) so that every child subnet is aligned to its size
+pub fn allocate(parent: &Ipv4Subnet, requirements: &[Requirement]) -> Vec<Allocation> {
+    let mut sorted = requirements.to_vec();
+    sorted.sort_by_key(|requirement| Reverse(requirement.hosts));
+
+    let end = u32::from(parent.broadcast()) as u64 + 1;
+    let mut cursor = u32::from(parent.network()) as u64;
+
+    sorted
+        .into_iter()
+        .map(|requirement| {
+            let subnet = prefix_for_hosts(requirement.hosts)
+                .filter(|prefix| *prefix >= parent.prefix())
+                .and_then(|prefix| {
+                    let size = 1u64 << (32 - prefix);
+                    let start = cursor.div_ceil(size) * size;
+                    if start + size > end {
+                        return None;
+                    }
+
+                    cursor = start + size;
+                    Ipv4Subnet::new(Ipv4Addr::from(start as u32), prefix)
+                });
+
+            Allocation {
+                name: requirement.name,
+                requested: Some(requirement.hosts),
+                subnet,
+            }
+        })
+        .collect()
+}
+
+/// Returns the longest prefix length whose subnet provides at least the given number of usable hosts
+pub fn prefix_for_hosts(hosts: u64) -> Option<u8> {
+    (0..=32u8)
+        .rev()
+        .find(|prefix| Ipv4Subnet::new(Ipv4Addr::UNSPECIFIED, *prefix).is_some_and(|s| s.host_count() >= hosts))
+}
+
+/// Parses one requirement per line as `name hosts`, `name: hosts`, `name, hosts`, or just `hosts`
+pub fn parse_requirements(input: &str) -> Result<Vec<Requirement>, String> {
+    let mut requirements = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let is_separator = |c: char| c.is_whitespace() || c == ':' || c == ',';
+        let (name, hosts) = match line.rfind(is_separator) {
+            Some(pos) => (line[..pos].trim_end_matches(is_separator), &line[pos + 1..]),
+            None => ("", line),
+        };
+
+        let hosts = match hosts.parse::<u64>() {
+            Ok(hosts) if hosts > 0 => hosts,
+            _ => return Err(format!("line {}: invalid host count", i + 1)),
+        };
+
+        let name = if name.is_empty() {
+            format!("subnet {}", requirements.len() + 1)
+        } else {
+            name.to_string()
+        };
+
+        requirements.push(Requirement { name, hosts });
+    }
+
+    Ok(requirements)
+}
+
+/// Exports the plan as CSV or Markdown table
+pub fn export(allocations: &[Allocation], format: ExportFormat) -> String {
+    let header = ["Name", "Requested", "Subnet", "First Host", "Last Host", "Broadcast", "Hosts"];
+    let rows: Vec<Vec<String>> = allocations
+        .iter()
+        .map(|allocation| {
+            let requested = allocation.requested.map(|r| r.to_string()).unwrap_or_default();
+            match &allocation.subnet {
+                Some(subnet) => vec![
+                    allocation.name.clone(),
+                    requested,
+                    subnet.to_string(),
+                    subnet.first_host().to_string(),
+                    subnet.last_host().to_string(),
+                    subnet.broadcast().to_string(),
+                    subnet.host_count().to_string(),
+                ],
+                None => vec![
+                    allocation.name.clone(),
+                    requested,
+                    "does not fit".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                    "".to_string(),
+                ],
+            }
+        })
+        .collect();
+
+    let mut out = String::new();
+    match format {
+        ExportFormat::Csv => {
+            out.push_str(&header.join(","));
+            out.push('\n');
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|cell| csv_escape(cell)).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Markdown => {
+            out.push_str(&format!("| {} |\n", header.join(" | ")));
+            out.push_str(&format!("|{}\n", "---|".repeat(header.len())));
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+    }
+    out
+}
+
+fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
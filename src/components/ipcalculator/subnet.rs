@@ -0,0 +1,124 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+/// An IPv4 address together with its prefix length, e.g. `10.20.0.0/19`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ipv4Subnet {
+    addr: Ipv4Addr,
+    prefix: u8,
+}
+
+impl Ipv4Subnet {
+    /// Creates a subnet from an address and a prefix length between 0 and 32
+    pub fn new(addr: Ipv4Addr, prefix: u8) -> Option<Self> {
+        if prefix > 32 {
+            return None;
+        }
+
+        Some(Ipv4Subnet { addr, prefix })
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    pub fn netmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from(prefix_to_mask(self.prefix))
+    }
+
+    pub fn wildcard(&self) -> Ipv4Addr {
+        Ipv4Addr::from(!prefix_to_mask(self.prefix))
+    }
+
+    pub fn network(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.addr) & prefix_to_mask(self.prefix))
+    }
+
+    pub fn broadcast(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.addr) | !prefix_to_mask(self.prefix))
+    }
+
+    /// Returns the first usable host address, /31 and /32 networks have no reserved addresses (RFC 3021)
+    pub fn first_host(&self) -> Ipv4Addr {
+        if self.prefix >= 31 {
+            self.network()
+        } else {
+            Ipv4Addr::from(u32::from(self.network()) + 1)
+        }
+    }
+
+    /// Returns the last usable host address, /31 and /32 networks have no reserved addresses (RFC 3021)
+    pub fn last_host(&self) -> Ipv4Addr {
+        if self.prefix >= 31 {
+            self.broadcast()
+        } else {
+            Ipv4Addr::from(u32::from(self.broadcast()) - 1)
+        }
+    }
+
+    /// Returns the number of addresses in the subnet including network and broadcast address
+    pub fn size(&self) -> u64 {
+        1u64 << (32 - self.prefix)
+    }
+
+    /// Returns the number of usable host addresses
+    pub fn host_count(&self) -> u64 {
+        if self.prefix >= 31 {
+            self.size()
+        } else {
+            self.size() - 2
+        }
+    }
+}
+
+impl fmt::Display for Ipv4Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Parses `10.20.0.0/19`, `10.20.0.0/255.255.224.0` or `10.20.0.0 255.255.224.0`
+impl FromStr for Ipv4Subnet {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, mask) = match s.split_once('/') {
+            Some((addr, mask)) => (addr.trim(), mask.trim()),
+            None => match s.split_once(char::is_whitespace) {
+                Some((addr, mask)) => (addr.trim(), mask.trim()),
+                None => (s, "32"),
+            },
+        };
+
+        let addr = addr.parse::<Ipv4Addr>().map_err(|_| "invalid IPv4 address")?;
+        let prefix = if mask.contains('.') {
+            let mask = mask.parse::<Ipv4Addr>().map_err(|_| "invalid netmask")?;
+            mask_to_prefix(u32::from(mask)).ok_or("netmask is not contiguous")?
+        } else {
+            mask.parse::<u8>().map_err(|_| "invalid prefix length")?
+        };
+
+        Ipv4Subnet::new(addr, prefix).ok_or("prefix length must be between 0 and 32")
+    }
+}
+
+/// Converts a prefix length to its netmask, e.g. 19 to 0xFFFFE000
+pub fn prefix_to_mask(prefix: u8) -> u32 {
+    if prefix == 0 {
+        0
+    } else {
+        u32::MAX << (32 - prefix)
+    }
+}
+
+/// Converts a netmask to its prefix length or returns none if the mask has holes
+pub fn mask_to_prefix(mask: u32) -> Option<u8> {
+    let prefix = mask.leading_ones() as u8;
+    if prefix_to_mask(prefix) == mask {
+        Some(prefix)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,140 @@
+use super::subnet::Ipv4Subnet;
+
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+/// A set of IPv4 addresses stored as sorted, non-overlapping, half-open ranges
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ipv4Set {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl Ipv4Set {
+    /// Creates a set from arbitrary ranges by sorting and merging overlapping or adjacent ones
+    fn from_ranges(mut ranges: Vec<(u64, u64)>) -> Self {
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Ipv4Set { ranges: merged }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn union(&self, other: &Ipv4Set) -> Ipv4Set {
+        Ipv4Set::from_ranges(self.ranges.iter().chain(other.ranges.iter()).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Ipv4Set) -> Ipv4Set {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let start = a.0.max(b.0);
+            let end = a.1.min(b.1);
+            if start < end {
+                ranges.push((start, end));
+            }
+
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Ipv4Set { ranges }
+    }
+
+    pub fn difference(&self, other: &Ipv4Set) -> Ipv4Set {
+        let mut ranges = Vec::new();
+
+        for &(start, end) in &self.ranges {
+            let mut start = start;
+            for &(other_start, other_end) in &other.ranges {
+                if other_end <= start || other_start >= end {
+                    continue;
+                }
+                if other_start > start {
+                    ranges.push((start, other_start));
+                }
+                start = start.max(other_end);
+            }
+            if start < end {
+                ranges.push((start, end));
+            }
+        }
+
+        Ipv4Set { ranges }
+    }
+
+    /// Returns the minimal list of CIDR blocks covering exactly this set
+    pub fn to_cidrs(&self) -> Vec<Ipv4Subnet> {
+        let mut cidrs = Vec::new();
+
+        for &(start, end) in &self.ranges {
+            let mut start = start;
+            while start < end {
+                let aligned = if start == 0 { 1u64 << 32 } else { 1u64 << start.trailing_zeros().min(32) };
+                let fitting = 1u64 << (63 - (end - start).leading_zeros());
+                let size = aligned.min(fitting);
+                let prefix = 32 - size.trailing_zeros() as u8;
+
+                cidrs.extend(Ipv4Subnet::new(Ipv4Addr::from(start as u32), prefix));
+                start += size;
+            }
+        }
+
+        cidrs
+    }
+}
+
+/// Parses addresses, CIDR blocks, and ranges like `10.0.0.5 - 10.0.0.90` separated by new lines, commas, or
+/// semicolons
+impl FromStr for Ipv4Set {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges = Vec::new();
+
+        for item in s.split(['\n', ',', ';']).map(str::trim).filter(|item| !item.is_empty()) {
+            let range = match item.split_once('-') {
+                Some((start, end)) => {
+                    let start = start.trim().parse::<Ipv4Addr>().map_err(|_| format!("{}: invalid start address", item))?;
+                    let end = end.trim().parse::<Ipv4Addr>().map_err(|_| format!("{}: invalid end address", item))?;
+                    if start > end {
+                        return Err(format!("{}: start address is greater than end address", item));
+                    }
+
+                    (u32::from(start) as u64, u32::from(end) as u64 + 1)
+                }
+                None => {
+                    let subnet = item.parse::<Ipv4Subnet>().map_err(|e| format!("{}: {}", item, e))?;
+                    (u32::from(subnet.network()) as u64, u32::from(subnet.broadcast()) as u64 + 1)
+                }
+            };
+
+            ranges.push(range);
+        }
+
+        Ok(Ipv4Set::from_ranges(ranges))
+    }
+}
+
+/// Formats CIDR blocks one per line
+pub fn format_cidrs(cidrs: &[Ipv4Subnet]) -> String {
+    cidrs
+        .iter()
+        .map(|cidr| cidr.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
@@ -74,6 +74,7 @@ impl Component for Navbar {
                                     <li class="uk-nav-header">{ "Tools" }</li>
                                     <li><a href="#numcalculator">{ "Numeric Calculator" }</a></li>
                                     <li><a href="#numconverter">{ "Numeral Converter" }</a></li>
+                                    <li><a href="#floatinspector">{ "Float Inspector" }</a></li>
                                     <li><a href="#ipcalculator">{ "IP Calculator" }</a></li>
                                     <li><a href="#timestampconverter">{ "Timestamp Converter" }</a></li>
                                     <li><a href="#chmodcalculator">{ "CHMOD Calculator" }</a></li>
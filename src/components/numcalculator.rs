@@ -1,14 +1,77 @@
+mod exact;
+mod history;
+mod message;
+mod plot;
+mod plotter;
+mod programmer;
+mod solver;
+mod statistics;
+mod units;
+mod worksheet;
+
+use crate::components::common::bitwidth;
 use crate::components::copytoclipboard::CopyToClipboard;
 
+use self::exact::Notation;
+use self::history::Entry;
+use self::plot::Function;
+use self::plotter::Plotter;
+use self::programmer::WordSize;
+use self::statistics::Value;
+use self::worksheet::{Line, Outcome};
+use num_bigint::BigInt;
+use wasm_bindgen::JsCast;
+use yew::format::Json;
 use yew::prelude::*;
+use yew::services::storage::{Area, StorageService};
+use yew::web_sys::HtmlTextAreaElement;
+
+const WORKSHEET_KEY: &str = "numcalculator_worksheet";
+const HISTORY_KEY: &str = "numcalculator_history";
+const DEFAULT_DIGITS: usize = 20;
+const MAX_DIGITS: usize = 1000;
 
 pub struct NumCalculator {
     link: ComponentLink<Self>,
+    storage: Result<StorageService, &'static str>,
+    mode: NumCalculatorMode,
+    size: WordSize,
+    notation: Notation,
+    digits: usize,
+    worksheet: String,
+    lines: Vec<Line<String>>,
     result: String,
+    integer: Option<BigInt>,
+    functions: Vec<Function>,
+    samples: String,
+    summary: Vec<(&'static str, Option<f64>)>,
+    samples_error: String,
+    stale: bool,
+    error: Option<String>,
+    method: String,
+    history: Vec<Entry>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum NumCalculatorMode {
+    Standard,
+    Programmer,
+    Units,
+    Exact,
+    Solve,
 }
 
 pub enum NumCalculatorMsg {
-    Eval(String)
+    ChangeMode(NumCalculatorMode),
+    ChangeWidth(Option<u32>),
+    ChangeSigned(bool),
+    ChangeNotation(Notation),
+    ChangeDigits(String),
+    Eval(String),
+    Commit(usize),
+    Recall(usize),
+    ClearHistory,
+    ChangeSamples(String),
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -20,10 +83,38 @@ impl Component for NumCalculator {
     type Properties = Props;
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        NumCalculator {
+        let storage = StorageService::new(Area::Local);
+        let (worksheet, history) = match &storage {
+            Ok(storage) => {
+                let worksheet = if let Json(Ok(worksheet)) = storage.restore(WORKSHEET_KEY) { worksheet } else { "".to_string() };
+                let history = if let Json(Ok(history)) = storage.restore(HISTORY_KEY) { history } else { Vec::new() };
+                (worksheet, history)
+            },
+            Err(_) => ("".to_string(), Vec::new()),
+        };
+
+        let mut calculator = NumCalculator {
             link: link,
+            storage,
+            mode: NumCalculatorMode::Standard,
+            size: WordSize { width: Some(64), signed: true },
+            notation: Notation::Auto,
+            digits: DEFAULT_DIGITS,
+            worksheet: "".to_string(),
+            lines: Vec::new(),
             result: "".to_string(),
-        }
+            integer: None,
+            functions: Vec::new(),
+            samples: "".to_string(),
+            summary: Vec::new(),
+            samples_error: "".to_string(),
+            stale: false,
+            error: None,
+            method: "".to_string(),
+            history,
+        };
+        calculator.eval(worksheet);
+        calculator
     }
 
     fn change(&mut self, _props: Self::Properties) -> ShouldRender {
@@ -32,46 +123,496 @@ impl Component for NumCalculator {
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            NumCalculatorMsg::Eval(expression) => {
-                let mut ns = fasteval::EmptyNamespace;
-                let ez = fasteval::ez_eval(&expression, &mut ns);
-                match ez {
-                    Ok(result) => { 
-                        self.result = result.to_string();
+            NumCalculatorMsg::ChangeMode(mode) => {
+                self.mode = mode;
+                self.result.clear();
+                self.eval(self.worksheet.clone());
+                true
+            }
+            NumCalculatorMsg::ChangeWidth(width) => {
+                self.size.width = width;
+                self.eval(self.worksheet.clone());
+                true
+            }
+            NumCalculatorMsg::ChangeSigned(signed) => {
+                self.size.signed = signed;
+                self.eval(self.worksheet.clone());
+                true
+            }
+            NumCalculatorMsg::ChangeNotation(notation) => {
+                self.notation = notation;
+                self.eval(self.worksheet.clone());
+                true
+            }
+            NumCalculatorMsg::ChangeDigits(digits) => {
+                self.digits = digits.trim().parse::<usize>().map_or(DEFAULT_DIGITS, |digits| digits.clamp(1, MAX_DIGITS));
+                self.eval(self.worksheet.clone());
+                true
+            }
+            NumCalculatorMsg::Eval(worksheet) => {
+                self.eval(worksheet);
+
+                if let Ok(storage) = &mut self.storage {
+                    storage.store(WORKSHEET_KEY, Json(&self.worksheet))
+                }
+
+                return true;
+            }
+            NumCalculatorMsg::Commit(index) => {
+                let expression = self.worksheet.lines().nth(index).unwrap_or_default().trim().to_string();
+                match self.lines.get(index).map(|line| &line.outcome) {
+                    Some(Outcome::Value(value)) => {
+                        history::push(&mut self.history, Entry { expression, result: value.to_string() });
+                        self.store_history();
+                        true
                     }
-                    Err(_e) => {
-                        self.result = "".to_string();
+                    _ => false,
+                }
+            }
+            NumCalculatorMsg::Recall(index) => {
+                if let Some(entry) = self.history.get(index) {
+                    let mut worksheet = self.worksheet.trim_end().to_string();
+                    if !worksheet.is_empty() {
+                        worksheet.push('\n');
                     }
+                    worksheet.push_str(&entry.expression);
+                    self.link.send_message(NumCalculatorMsg::Eval(worksheet));
                 }
-                return true;
+                false
+            }
+            NumCalculatorMsg::ClearHistory => {
+                self.history.clear();
+                self.store_history();
+                true
+            }
+            NumCalculatorMsg::ChangeSamples(samples) => {
+                match statistics::parse_samples(&samples) {
+                    Ok(values) if values.is_empty() => {
+                        self.summary.clear();
+                        self.samples_error.clear();
+                    }
+                    Ok(values) => {
+                        self.summary = statistics::summary(&values);
+                        self.samples_error.clear();
+                    }
+                    Err(e) => self.samples_error = e,
+                }
+
+                self.samples = samples;
+                true
             }
         }
     }
 
     fn view(&self) -> Html {
+        let rows = (self.lines.len() + 1).max(3).to_string();
+        let placeholder = match self.mode {
+            NumCalculatorMode::Standard => "rate = 1.2e6\nrate * 60",
+            NumCalculatorMode::Programmer => "mask = 0xFF00\n(flags & mask) >> 8",
+            NumCalculatorMode::Units => "size = 1.5 GiB\nsize / 200 Mbit/s in minutes",
+            NumCalculatorMode::Exact => "price = 19.99\nprice * 3 / 7",
+            NumCalculatorMode::Solve => "fixed = 1000\nfixed + 5 * x = 12 * x",
+        };
+
         html! {
             <form class="uk-grid-small" uk-grid="">
-                <div class="uk-width-4-4">
-                    <label class="uk-form-label" for="expression">{ "Math Expression" }</label>
+                <div class="uk-width-1-1">
+                    <ul class="uk-subnav uk-subnav-pill uk-margin-remove-bottom">
+                        { self.mode_tab("Standard", NumCalculatorMode::Standard) }
+                        { self.mode_tab("Programmer", NumCalculatorMode::Programmer) }
+                        { self.mode_tab("Units", NumCalculatorMode::Units) }
+                        { self.mode_tab("Exact", NumCalculatorMode::Exact) }
+                        { self.mode_tab("Solve", NumCalculatorMode::Solve) }
+                    </ul>
+                </div>
+                { if self.mode == NumCalculatorMode::Programmer { self.view_word_size() } else { html! {} } }
+                { if self.mode == NumCalculatorMode::Exact { self.view_notation() } else { html! {} } }
+                <div class="uk-width-2-3">
+                    <label class="uk-form-label" for="expression">{ "Math Expressions" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea uk-form-large mousetrap"
+                                  id="expression"
+                                  rows=rows.clone()
+                                  wrap="off"
+                                  placeholder=placeholder
+                                  oninput=self.link.callback(|d: InputData| NumCalculatorMsg::Eval(d.value))
+                                  onkeydown=self.link.batch_callback(commit_line)
+                                  value=self.worksheet.clone() />
+                    </div>
+                </div>
+                <div class="uk-width-1-3">
+                    <label class="uk-form-label" for="results">{ "Line Results" }</label>
                     <div class="uk-form-controls">
-                        <input class="uk-input uk-form-large mousetrap" 
-                               id="expression"
-                               oninput=self.link.callback(|d: InputData| NumCalculatorMsg::Eval(d.value)) />
+                        <textarea class="uk-textarea uk-form-large"
+                                  id="results"
+                                  rows=rows
+                                  wrap="off"
+                                  value=self.line_results()
+                                  readonly=true />
                     </div>
                 </div>
                 <div class="uk-width-1-4">
-                    <label class="uk-form-label" for="result">{ "Result" }</label>
+                    <label class="uk-form-label" for="result">
+                        { "Result" }
+                        { if self.stale { html! { <span class="uk-text-muted">{ " (stale)" }</span> } } else { html! {} } }
+                    </label>
                     <div class="uk-form-controls">
                         <div class="uk-inline">
                             <CopyToClipboard from="result" />
                             <input class="uk-input uk-form-large"
                                    id="result"
+                                   style=if self.stale { "opacity: 0.5" } else { "" }
                                    value=self.result.clone()
                                    disabled=true />
                         </div>
                     </div>
                 </div>
+                <div class="uk-width-3-4">
+                    <label class="uk-form-label">{ '\u{00a0}' }</label>
+                    <div class="uk-text-danger uk-margin-small-top">{ self.error.clone().unwrap_or_default() }</div>
+                    <div class="uk-text-muted uk-text-small">{ self.method.clone() }</div>
+                </div>
+                { if self.mode == NumCalculatorMode::Programmer { self.view_bases() } else { html! {} } }
+                { if self.functions.is_empty() { html! {} } else { html! { <Plotter functions=self.functions.clone() /> } } }
+                { if self.mode == NumCalculatorMode::Standard { self.view_statistics() } else { html! {} } }
+                { self.view_history() }
             </form>
         }
     }
 }
+
+impl NumCalculator {
+    /// Creates a tab of the mode selection
+    fn mode_tab(&self, text: &str, mode: NumCalculatorMode) -> Html {
+        let class = if self.mode == mode { "uk-active" } else { "" };
+        let onclick = self.link.callback(move |e: MouseEvent| {
+            e.prevent_default();
+            NumCalculatorMsg::ChangeMode(mode)
+        });
+
+        html! {
+            <li class=class>
+                <a href="#" onclick=onclick>{ text }</a>
+            </li>
+        }
+    }
+
+    /// Creates the word size and signedness selection of the programmer mode
+    fn view_word_size(&self) -> Html {
+        html! {
+            <div class="uk-width-1-1">
+                <div class="uk-button-group uk-margin-small-right">
+                    { self.width_button("\u{221e}", None) }
+                    { for bitwidth::WIDTHS.iter().map(|width| self.width_button(&width.to_string(), Some(*width))) }
+                </div>
+                <div class="uk-button-group">
+                    { self.signed_button("signed", true) }
+                    { self.signed_button("unsigned", false) }
+                </div>
+            </div>
+        }
+    }
+
+    /// Creates a button of the word size selection
+    fn width_button(&self, text: &str, width: Option<u32>) -> Html {
+        let class = if self.size.width == width { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| NumCalculatorMsg::ChangeWidth(width))>
+                { text }
+            </button>
+        }
+    }
+
+    /// Creates a button of the signed or unsigned selection
+    fn signed_button(&self, text: &str, signed: bool) -> Html {
+        let class = if self.size.signed == signed { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| NumCalculatorMsg::ChangeSigned(signed))>
+                { text }
+            </button>
+        }
+    }
+
+    /// Creates the notation and digits selection of the exact mode
+    fn view_notation(&self) -> Html {
+        html! {
+            <div class="uk-width-1-1">
+                <div class="uk-button-group uk-margin-small-right">
+                    { self.notation_button("auto", Notation::Auto) }
+                    { self.notation_button("scientific", Notation::Scientific) }
+                    { self.notation_button("engineering", Notation::Engineering) }
+                </div>
+                <label class="uk-form-label uk-margin-small-right" for="digits">{ "Digits" }</label>
+                <input class="uk-input uk-form-small uk-form-width-xsmall"
+                       id="digits"
+                       type="number"
+                       min="1"
+                       max=MAX_DIGITS.to_string()
+                       uk-tooltip="decimal places and longest number in auto notation, significant digits otherwise"
+                       oninput=self.link.callback(|d: InputData| NumCalculatorMsg::ChangeDigits(d.value))
+                       value=self.digits.to_string() />
+            </div>
+        }
+    }
+
+    /// Creates a button of the notation selection
+    fn notation_button(&self, text: &str, notation: Notation) -> Html {
+        let class = if self.notation == notation { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| NumCalculatorMsg::ChangeNotation(notation))>
+                { text }
+            </button>
+        }
+    }
+
+    /// Creates the result of the programmer mode in all bases, as two's complement pattern if a word size is selected
+    fn view_bases(&self) -> Html {
+        let format = |radix: u32| match (&self.integer, self.size.width) {
+            (Some(value), Some(width)) if radix != 10 => {
+                let pattern = bitwidth::wrap(value, width).to_str_radix(radix).to_uppercase();
+                format!("{:0>1$}", pattern, bitwidth::digits(width, radix))
+            }
+            (Some(value), _) => value.to_str_radix(radix).to_uppercase(),
+            (None, _) => "".to_string(),
+        };
+
+        html! {
+            <>
+                { self.output("uk-width-1-4", "resulthex", "Hexadecimal", format(16)) }
+                { self.output("uk-width-1-4", "resultdec", "Decimal", format(10)) }
+                { self.output("uk-width-1-2", "resultoct", "Octal", format(8)) }
+                { self.output("uk-width-1-1", "resultbin", "Binary", format(2)) }
+            </>
+        }
+    }
+
+    /// Creates the input for pasted samples and their summary table
+    fn view_statistics(&self) -> Html {
+        html! {
+            <>
+                <div class="uk-width-1-3">
+                    <label class="uk-form-label" for="samples">{ "Samples" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea"
+                                  id="samples"
+                                  rows="8"
+                                  placeholder="one number per line or comma-separated"
+                                  oninput=self.link.callback(|d: InputData| NumCalculatorMsg::ChangeSamples(d.value))
+                                  value=self.samples.clone() />
+                    </div>
+                    <div class="uk-text-danger uk-text-small">{ self.samples_error.clone() }</div>
+                </div>
+                <div class="uk-width-2-3">
+                    <table class="uk-table uk-table-small uk-table-divider uk-margin-remove">
+                        <tbody>
+                            { for self.summary.iter().map(|(name, value)| html! {
+                                <tr>
+                                    <td>{ name }</td>
+                                    <td>{ value.map(|value| value.to_string()).unwrap_or_default() }</td>
+                                </tr>
+                            }) }
+                        </tbody>
+                    </table>
+                </div>
+            </>
+        }
+    }
+
+    /// Creates a read-only field with a copy to clipboard button
+    fn output(&self, width: &str, id: &str, text: &str, value: String) -> Html {
+        let style = if self.stale { "opacity: 0.5" } else { "" };
+
+        html! {
+            <div class=width.to_string()>
+                <label class="uk-form-label" for=id.to_string()>{ text }</label>
+                <div class="uk-form-controls">
+                    <div class="uk-inline uk-width-expand">
+                        <CopyToClipboard from=id.to_string() />
+                        <input class="uk-input"
+                               id=id.to_string()
+                               type="text"
+                               style=style
+                               value=value
+                               disabled=true />
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    /// Stores the history in the local storage
+    fn store_history(&mut self) {
+        if let Ok(storage) = &mut self.storage {
+            storage.store(HISTORY_KEY, Json(&self.history))
+        }
+    }
+
+    /// Creates the list of committed expressions, clicking an expression appends it to the worksheet
+    fn view_history(&self) -> Html {
+        if self.history.is_empty() {
+            return html! {};
+        }
+
+        html! {
+            <div class="uk-width-1-1">
+                <label class="uk-form-label">{ "History" }</label>
+                <button class="uk-button uk-button-link uk-margin-small-left"
+                        type="button"
+                        onclick=self.link.callback(|_| NumCalculatorMsg::ClearHistory)>
+                    { "clear" }
+                </button>
+                <table class="uk-table uk-table-small uk-table-divider uk-table-hover uk-margin-remove">
+                    <tbody>
+                        { for self.history.iter().enumerate().map(|(index, entry)| html! {
+                            <tr>
+                                <td class="uk-table-expand">
+                                    <a href="#"
+                                       uk-tooltip="add to worksheet"
+                                       onclick=self.link.callback(move |e: MouseEvent| {
+                                           e.prevent_default();
+                                           NumCalculatorMsg::Recall(index)
+                                       })>
+                                        <code>{ entry.expression.clone() }</code>
+                                    </a>
+                                </td>
+                                <td class="uk-width-1-4">
+                                    <div class="uk-inline uk-width-expand">
+                                        <CopyToClipboard from=format!("history{}", index) />
+                                        <input class="uk-input uk-form-small"
+                                               id=format!("history{}", index)
+                                               value=entry.result.clone()
+                                               disabled=true />
+                                    </div>
+                                </td>
+                            </tr>
+                        }) }
+                    </tbody>
+                </table>
+            </div>
+        }
+    }
+
+    /// Evaluates all lines of the worksheet, the result is the one of the last line, which is kept as stale while the
+    /// last line has an error, the functions of `x` in the standard mode are plotted, and in the solve mode the result
+    /// is the solution of the equations
+    fn eval(&mut self, worksheet: String) {
+        let mut functions = Vec::new();
+        let mut solution = None;
+        let (lines, integer) = match self.mode {
+            NumCalculatorMode::Standard => {
+                let lines = worksheet::evaluate(&worksheet, worksheet::eval_float);
+                for line in &lines {
+                    match &line.outcome {
+                        Outcome::Value(Value::Function(function)) if !functions.contains(function) => functions.push(function.clone()),
+                        _ => {}
+                    }
+                }
+                (lines.into_iter().map(|line| line.map(|value| value.to_string())).collect(), None)
+            }
+            NumCalculatorMode::Programmer => {
+                let size = self.size;
+                let lines = worksheet::evaluate(&worksheet, |expression, offset, variables| {
+                    programmer::evaluate(expression, offset, variables, size)
+                });
+                let integer = match worksheet::last(&lines) {
+                    Some((_, Outcome::Value(value))) => Some(value.clone()),
+                    _ => None,
+                };
+                (lines.into_iter().map(|line| line.map(|value| value.to_string())).collect(), integer)
+            }
+            NumCalculatorMode::Units => {
+                let lines = worksheet::evaluate(&worksheet, |expression, offset, variables| {
+                    units::evaluate(expression, offset, variables)
+                });
+                (lines.into_iter().map(|line| line.map(|value| value.to_string())).collect(), None)
+            }
+            NumCalculatorMode::Exact => {
+                let lines = worksheet::evaluate(&worksheet, |expression, offset, variables| {
+                    exact::evaluate(expression, offset, variables)
+                });
+                let (notation, digits) = (self.notation, self.digits);
+                (lines.into_iter().map(|line| line.map(|value| exact::format(&value, notation, digits))).collect(), None)
+            }
+            NumCalculatorMode::Solve => {
+                let (lines, solved) = solver::solve(&worksheet);
+                solution = solved;
+                (lines, None)
+            }
+        };
+        self.lines = lines;
+        self.functions = functions;
+        self.error = None;
+        self.method.clear();
+
+        // the solution of the equations replaces the result of the last line, unless that line has an error itself
+        let last = match solution {
+            Some(solved) if !matches!(worksheet::last(&self.lines), Some((_, Outcome::Error(_)))) => Some((None, match solved {
+                Ok(solution) => {
+                    let values: Vec<String> = solution.values.iter().map(|(name, value)| format!("{} = {}", name, solver::round(*value))).collect();
+                    self.method = solution.method;
+                    Outcome::Value(values.join(", "))
+                }
+                Err(e) => Outcome::Error(e),
+            })),
+            _ => worksheet::last(&self.lines).map(|(index, outcome)| (Some(index), outcome.clone())),
+        };
+
+        match last {
+            Some((_, Outcome::Value(value))) => {
+                self.result = value;
+                self.integer = integer;
+                self.stale = false;
+            }
+            Some((index, Outcome::Error(e))) => {
+                self.error = Some(match index {
+                    Some(index) => format!("line {}: {}", index + 1, e),
+                    None => e,
+                });
+                self.stale = !self.result.is_empty();
+            }
+            _ => {
+                self.result.clear();
+                self.integer = None;
+                self.stale = false;
+            }
+        }
+
+        self.worksheet = worksheet;
+    }
+
+    /// Returns the results of the lines, one per line to line up with the worksheet
+    fn line_results(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| match &line.outcome {
+                Outcome::Empty => "".to_string(),
+                Outcome::Value(value) => match &line.name {
+                    Some(name) => format!("{} = {}", name, value),
+                    None => value.to_string(),
+                },
+                Outcome::Error(e) => format!("error: {}", e),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Commits the line the cursor is on when Enter is pressed
+fn commit_line(e: KeyboardEvent) -> Option<NumCalculatorMsg> {
+    if e.key() != "Enter" || e.shift_key() {
+        return None;
+    }
+
+    let textarea = e.target()?.dyn_into::<HtmlTextAreaElement>().ok()?;
+    let cursor = textarea.selection_start().ok()??;
+    Some(NumCalculatorMsg::Commit(history::line_at(&textarea.value(), cursor as usize)))
+}
@@ -0,0 +1,304 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Pow, Signed, ToPrimitive, Zero};
+use std::collections::BTreeMap;
+
+/// The largest exponent of a power or a number literal
+const MAX_EXPONENT: i32 = 10000;
+
+/// The most bits of the numerator or denominator of a result, to keep the numbers in memory
+const MAX_BITS: u64 = 100_000;
+
+/// How exact results are written
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Notation {
+    Auto,
+    Scientific,
+    Engineering,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(BigRational),
+    Name(String),
+    Operator(char),
+}
+
+/// Evaluates an expression of exact rational numbers with `+ - * / ^` and parentheses, positions in errors are shifted
+/// by the offset of the expression within its line
+pub fn evaluate(expression: &str, offset: usize, variables: &BTreeMap<String, BigRational>) -> Result<BigRational, String> {
+    let tokens = tokenize(expression, offset)?;
+    let mut parser = Parser { tokens, index: 0, variables, end: offset + expression.chars().count() + 1 };
+
+    let value = parser.sum()?;
+    match parser.tokens.get(parser.index) {
+        Some((token, position)) => Err(format!("unexpected {} at position {}, an operator may be missing", describe(token), position)),
+        None => Ok(value),
+    }
+}
+
+fn tokenize(expression: &str, offset: usize) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let position = offset + i + 1;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.' || chars[i] == '_') {
+                // the sign of an exponent belongs to the number
+                if (chars[i] == 'e' || chars[i] == 'E') && matches!(chars.get(i + 1), Some('-' | '+')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+
+            let literal: String = chars[start..i].iter().collect();
+            match parse_decimal(&literal) {
+                Some(value) => tokens.push((Token::Number(value), position)),
+                None => return Err(format!("invalid number '{}' at position {}", literal, position)),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Name(chars[start..i].iter().collect()), position));
+        } else if "+-*/^()".contains(c) {
+            tokens.push((Token::Operator(c), position));
+            i += 1;
+        } else {
+            return Err(format!("unexpected '{}' at position {}", c, position));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses a decimal number like `0.1`, `1_000`, or `2.5e-3` exactly
+fn parse_decimal(literal: &str) -> Option<BigRational> {
+    let literal = literal.replace('_', "");
+    let (mantissa, exponent) = match literal.find(['e', 'E']) {
+        Some(index) => (&literal[..index], literal[index + 1..].parse::<i32>().ok()?),
+        None => (literal.as_str(), 0),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if integer.is_empty() && fraction.is_empty() || !format!("{}{}", integer, fraction).chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if exponent.unsigned_abs() > MAX_EXPONENT as u32 {
+        return None;
+    }
+
+    let digits = format!("{}{}", integer, fraction).parse::<BigInt>().ok()?;
+    let exponent = exponent - fraction.len() as i32;
+    Some(BigRational::from_integer(digits) * power_of_ten(exponent))
+}
+
+fn power_of_ten(exponent: i32) -> BigRational {
+    Pow::pow(BigRational::from_integer(BigInt::from(10)), exponent)
+}
+
+/// Returns the bits of the larger of numerator and denominator
+fn bits(value: &BigRational) -> u64 {
+    value.numer().bits().max(value.denom().bits())
+}
+
+/// Rejects a result whose estimated size exceeds the bits kept in memory
+fn limit(operator: char, position: usize, bits: u64) -> Result<(), String> {
+    if bits > MAX_BITS {
+        return Err(format!("result of '{}' at position {} is too large", operator, position));
+    }
+    Ok(())
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(value) => format!("'{}'", value),
+        Token::Name(name) => format!("'{}'", name),
+        Token::Operator(operator) => format!("'{}'", operator),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    variables: &'a BTreeMap<String, BigRational>,
+    end: usize,
+}
+
+impl Parser<'_> {
+    /// Consumes the next token if it is one of the operators and returns it with its position
+    fn operator(&mut self, operators: &str) -> Option<(char, usize)> {
+        match self.tokens.get(self.index) {
+            Some((Token::Operator(operator), position)) if operators.contains(*operator) => {
+                self.index += 1;
+                Some((*operator, *position))
+            }
+            _ => None,
+        }
+    }
+
+    fn sum(&mut self) -> Result<BigRational, String> {
+        let mut value = self.product()?;
+        while let Some((operator, position)) = self.operator("+-") {
+            let rhs = self.product()?;
+            limit(operator, position, bits(&value) + bits(&rhs) + 1)?;
+            value = if operator == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<BigRational, String> {
+        let mut value = self.unary()?;
+        while let Some((operator, position)) = self.operator("*/") {
+            let rhs = self.unary()?;
+            limit(operator, position, bits(&value) + bits(&rhs))?;
+            value = match operator {
+                '/' if rhs.is_zero() => return Err(format!("division by zero at position {}", position)),
+                '/' => value / rhs,
+                _ => value * rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<BigRational, String> {
+        match self.operator("-+") {
+            Some(('-', _)) => Ok(-self.unary()?),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<BigRational, String> {
+        let base = self.primary()?;
+        let position = match self.operator("^") {
+            Some((_, position)) => position,
+            None => return Ok(base),
+        };
+
+        let exponent = self.unary()?;
+        if !exponent.is_integer() {
+            return Err(format!("exponent at position {} must be an integer to stay exact", position + 1));
+        }
+        let exponent = match exponent.to_integer().to_i32() {
+            Some(exponent) if exponent.abs() <= MAX_EXPONENT => exponent,
+            _ => return Err(format!("exponent at position {} is too large", position + 1)),
+        };
+        if base.is_zero() && exponent < 0 {
+            return Err(format!("division by zero at position {}", position));
+        }
+
+        limit('^', position, bits(&base) * exponent.unsigned_abs() as u64)?;
+        Ok(Pow::pow(base, exponent))
+    }
+
+    fn primary(&mut self) -> Result<BigRational, String> {
+        let (token, position) = match self.tokens.get(self.index) {
+            Some(token) => token.clone(),
+            None => return Err(format!("incomplete expression, a value is missing at position {}", self.end)),
+        };
+        self.index += 1;
+
+        match token {
+            Token::Number(value) => Ok(value),
+            Token::Name(name) => match self.variables.get(&name) {
+                Some(value) => Ok(value.clone()),
+                None => Err(format!("unknown variable '{}' at position {}", name, position)),
+            },
+            Token::Operator('(') => {
+                let value = self.sum()?;
+                match self.operator(")") {
+                    Some(_) => Ok(value),
+                    None => Err(format!("unclosed '(' at position {}", position)),
+                }
+            }
+            token => Err(format!("unexpected {} at position {}", describe(&token), position)),
+        }
+    }
+}
+
+/// Formats the value with the given number of digits, in auto notation an exact decimal if it has at most that many
+/// decimal places, else its rounded decimal with the fraction if numerator and denominator have at most that many
+/// digits, and values too large or too small for the digits in scientific notation
+pub fn format(value: &BigRational, notation: Notation, digits: usize) -> String {
+    match notation {
+        Notation::Auto => {
+            if value.abs().to_integer().to_string().len() > digits.max(1) {
+                return to_scientific(value, digits.max(1), 1);
+            }
+            if value.is_integer() {
+                return value.to_integer().to_string();
+            }
+
+            let (decimal, exact) = to_decimal(value, digits);
+            let short = |part: &BigInt| part.magnitude().to_string().len() <= digits;
+            if exact {
+                decimal
+            } else if decimal == "0" {
+                to_scientific(value, digits.max(1), 1)
+            } else if short(value.numer()) && short(value.denom()) {
+                format!("{} \u{2248} {}", value, decimal)
+            } else {
+                format!("\u{2248} {}", decimal)
+            }
+        }
+        Notation::Scientific => to_scientific(value, digits.max(1), 1),
+        Notation::Engineering => to_scientific(value, digits.max(1), 3),
+    }
+}
+
+/// Rounds the value half away from zero to the given number of decimal places, trailing zeros are removed, and tells
+/// whether the result is exact
+fn to_decimal(value: &BigRational, places: usize) -> (String, bool) {
+    let scaled = value.abs() * power_of_ten(places as i32);
+    let exact = scaled.is_integer();
+    let rounded = scaled.round().to_integer();
+
+    let mut digits = format!("{:0>1$}", rounded.to_string(), places + 1);
+    let fraction = digits.split_off(digits.len() - places);
+    let fraction = fraction.trim_end_matches('0');
+    let sign = if value.is_negative() && !rounded.is_zero() { "-" } else { "" };
+
+    if fraction.is_empty() {
+        (format!("{}{}", sign, digits), exact)
+    } else {
+        (format!("{}{}.{}", sign, digits, fraction), exact)
+    }
+}
+
+/// Writes the value with the given significant digits and an exponent that is a multiple of the step
+fn to_scientific(value: &BigRational, digits: usize, step: i32) -> String {
+    if value.is_zero() {
+        return "0".to_string();
+    }
+
+    // find the exponent with 1 <= mantissa < 10, then round and fix the exponent if the mantissa rounds up to 10
+    let abs = value.abs();
+    let mut exponent = abs.numer().to_string().len() as i32 - abs.denom().to_string().len() as i32;
+    while abs < power_of_ten(exponent) {
+        exponent -= 1;
+    }
+    while abs >= power_of_ten(exponent + 1) {
+        exponent += 1;
+    }
+
+    let mut rounded = (&abs / power_of_ten(exponent) * power_of_ten(digits as i32 - 1)).round();
+    if rounded >= power_of_ten(digits as i32) {
+        exponent += 1;
+        rounded = (&abs / power_of_ten(exponent) * power_of_ten(digits as i32 - 1)).round();
+    }
+
+    let shift = exponent.rem_euclid(step);
+    let mantissa = rounded / power_of_ten(digits as i32 - 1 - shift);
+    let (mantissa, _) = to_decimal(&mantissa, (digits as i32 - 1 - shift).max(0) as usize);
+    let sign = if value.is_negative() { "-" } else { "" };
+
+    format!("{}{}e{}", sign, mantissa, exponent - shift)
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// The number of entries kept in the history
+const MAX_ENTRIES: usize = 50;
+
+/// A committed expression with its result
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Entry {
+    pub expression: String,
+    pub result: String,
+}
+
+/// Adds the entry to the front of the history unless it repeats the latest one, dropping the oldest entries
+pub fn push(history: &mut Vec<Entry>, entry: Entry) {
+    if history.first() == Some(&entry) {
+        return;
+    }
+
+    history.insert(0, entry);
+    history.truncate(MAX_ENTRIES);
+}
+
+/// Returns the index of the line containing the cursor, which the browser gives in UTF-16 code units
+pub fn line_at(text: &str, cursor: usize) -> usize {
+    let mut offset = 0;
+    let mut line = 0;
+
+    for c in text.chars() {
+        if offset >= cursor {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+        }
+        offset += c.len_utf16();
+    }
+
+    line
+}
@@ -0,0 +1,81 @@
+use fasteval::Error;
+
+/// Describes why the expression could not be evaluated, positions count characters from 1 in the expression as typed,
+/// which the origins map the characters of a rewritten expression back to, and are shifted by the offset of the
+/// expression within its line
+pub fn describe(expression: &str, origins: &[usize], offset: usize, error: &Error) -> String {
+    let position = |index: usize| position(expression, origins, offset, index);
+    if let Some(message) = check_parentheses(expression, &position) {
+        return message;
+    }
+
+    match error {
+        Error::EOF | Error::EofWhileParsing(_) => "incomplete expression, a value is missing at the end".to_string(),
+        Error::UnparsedTokensRemaining(rest) => {
+            let index = expression.rfind(rest.as_str()).unwrap_or(0);
+            let token = rest.chars().next().unwrap_or(' ');
+            format!("unexpected '{}' at position {}, an operator may be missing", token, position(index))
+        }
+        Error::Undefined(name) => match find_name(expression, name) {
+            Some(index) => {
+                let kind = if expression[index + name.len()..].trim_start().starts_with('(') { "function" } else { "variable" };
+                format!("unknown {} '{}' at position {}", kind, name, position(index))
+            }
+            None => format!("unknown name '{}'", name),
+        },
+        Error::WrongArgs(message) => format!("wrong arguments, {}", message),
+        Error::ParseF64(number) => match expression.find(number.as_str()) {
+            Some(index) => format!("invalid number '{}' at position {}", number, position(index)),
+            None => format!("invalid number '{}'", number),
+        },
+        Error::InvalidValue => "invalid value, an operand is missing or not a number".to_string(),
+        Error::TooLong => "expression is too long".to_string(),
+        Error::TooDeep => "expression is nested too deeply".to_string(),
+        e => e.to_string(),
+    }
+}
+
+/// Describes a result that is no finite number
+pub fn describe_value(value: f64) -> Option<String> {
+    if value.is_nan() {
+        Some("result is not a number, e.g. 0/0 or an operation outside its domain".to_string())
+    } else if value.is_infinite() {
+        Some("result is infinite, e.g. division by zero or overflow".to_string())
+    } else {
+        None
+    }
+}
+
+/// Finds the first unmatched closing or unclosed opening parenthesis
+fn check_parentheses(expression: &str, position: &dyn Fn(usize) -> usize) -> Option<String> {
+    let mut open = Vec::new();
+
+    for (index, c) in expression.char_indices() {
+        match c {
+            '(' => open.push(index),
+            ')' if open.pop().is_none() => {
+                return Some(format!("unmatched ')' at position {}", position(index)));
+            }
+            _ => {}
+        }
+    }
+
+    open.first().map(|index| format!("unclosed '(' at position {}", position(*index)))
+}
+
+/// Finds the name as a whole word in the expression
+pub fn find_name(expression: &str, name: &str) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    expression.match_indices(name).map(|(index, _)| index).find(|index| {
+        let before = expression[..*index].chars().next_back();
+        let after = expression[index + name.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Returns the 1-based character position of a byte index in the expression as typed
+fn position(expression: &str, origins: &[usize], offset: usize, index: usize) -> usize {
+    let count = expression[..index].chars().count();
+    offset + origins.get(count).copied().unwrap_or(count) + 1
+}
@@ -0,0 +1,143 @@
+use super::statistics;
+use fasteval::{Compiler, Evaler};
+use std::collections::BTreeMap;
+
+/// The free variable of a plotted expression
+pub const VARIABLE: &str = "x";
+
+/// The size of the plot in SVG user units
+pub const WIDTH: f64 = 600.0;
+pub const HEIGHT: f64 = 300.0;
+
+/// The margins of the plot area, leaving room for the tick labels on the left and at the bottom
+const LEFT: f64 = 50.0;
+const RIGHT: f64 = 10.0;
+const TOP: f64 = 10.0;
+const BOTTOM: f64 = 25.0;
+
+/// The number of intervals a curve is sampled in
+const SAMPLES: usize = 300;
+
+/// An expression of `x` with the numbers of the worksheet it refers to
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function {
+    pub label: String,
+    pub expression: String,
+    pub constants: BTreeMap<String, f64>,
+}
+
+impl Function {
+    /// Evaluates the function at a single point
+    pub fn eval(&self, x: f64) -> Result<f64, fasteval::Error> {
+        fasteval::ez_eval(&self.expression, &mut |name: &str, args: Vec<f64>| self.call(name, &args, x))
+    }
+
+    /// Samples the function evenly over the range, points that are no finite number are left out
+    pub fn sample(&self, (min, max): (f64, f64)) -> Vec<Option<(f64, f64)>> {
+        let parser = fasteval::Parser::new();
+        let mut slab = fasteval::Slab::new();
+        let compiled = match parser.parse(&self.expression, &mut slab.ps) {
+            Ok(expression) => expression.from(&slab.ps).compile(&slab.ps, &mut slab.cs),
+            Err(_) => return Vec::new(),
+        };
+
+        (0..=SAMPLES)
+            .map(|i| {
+                let x = min + (max - min) * i as f64 / SAMPLES as f64;
+                let mut namespace = |name: &str, args: Vec<f64>| self.call(name, &args, x);
+                compiled.eval(&slab, &mut namespace).ok().filter(|y| y.is_finite()).map(|y| (x, y))
+            })
+            .collect()
+    }
+
+    fn call(&self, name: &str, args: &[f64], x: f64) -> Option<f64> {
+        if name == VARIABLE && args.is_empty() {
+            return Some(x);
+        }
+
+        match self.constants.get(name) {
+            Some(value) if args.is_empty() => Some(*value),
+            _ => statistics::call(name, args).ok(),
+        }
+    }
+}
+
+/// The ranges of the plot area and the conversion to SVG coordinates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frame {
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+}
+
+impl Frame {
+    /// Creates the frame for the x range with a y range fitting the sampled curves, ignoring the outermost percent of
+    /// the values so that poles do not flatten the rest of the curve
+    pub fn fit(x: (f64, f64), curves: &[Vec<Option<(f64, f64)>>]) -> Frame {
+        let ys: Vec<f64> = curves.iter().flatten().flatten().map(|(_, y)| *y).collect();
+        let (low, high) = match (statistics::percentile(&ys, 1.0), statistics::percentile(&ys, 99.0)) {
+            (Some(low), Some(high)) if high > low => (low, high),
+            (Some(value), _) => (value - 1.0, value + 1.0),
+            _ => (-1.0, 1.0),
+        };
+
+        let padding = (high - low) * 0.05;
+        Frame { x, y: (low - padding, high + padding) }
+    }
+
+    pub fn svg_x(&self, x: f64) -> f64 {
+        let (min, max) = self.x;
+        LEFT + (x - min) / (max - min) * (WIDTH - LEFT - RIGHT)
+    }
+
+    /// Converts y to SVG coordinates, clamped to well outside the plot area to keep the path small
+    pub fn svg_y(&self, y: f64) -> f64 {
+        let (min, max) = self.y;
+        (HEIGHT - BOTTOM - (y - min) / (max - min) * (HEIGHT - TOP - BOTTOM)).clamp(-HEIGHT, 2.0 * HEIGHT)
+    }
+
+    /// Returns the rectangle of the plot area as x, y, width, and height
+    pub fn area(&self) -> (f64, f64, f64, f64) {
+        (LEFT, TOP, WIDTH - LEFT - RIGHT, HEIGHT - TOP - BOTTOM)
+    }
+
+    /// Returns the SVG path of the sampled curve, starting a new segment after a gap
+    pub fn path(&self, points: &[Option<(f64, f64)>]) -> String {
+        let mut path = String::new();
+        let mut gap = true;
+
+        for point in points {
+            match point {
+                Some((x, y)) => {
+                    path.push_str(&format!("{}{:.1},{:.1} ", if gap { "M" } else { "L" }, self.svg_x(*x), self.svg_y(*y)));
+                    gap = false;
+                }
+                None => gap = true,
+            }
+        }
+
+        path.trim_end().to_string()
+    }
+}
+
+/// Returns the x value of the range at a fraction of the plot width, including the margins
+pub fn x_at((min, max): (f64, f64), fraction: f64) -> f64 {
+    min + (fraction * WIDTH - LEFT) / (WIDTH - LEFT - RIGHT) * (max - min)
+}
+
+/// Returns evenly spaced values within the range at a step of 1, 2, or 5 times a power of ten, with the number of
+/// decimals needed to tell them apart
+pub fn ticks((min, max): (f64, f64)) -> (Vec<f64>, usize) {
+    let rough = (max - min) / 8.0;
+    if !(rough.is_finite() && rough > 0.0) {
+        return (Vec::new(), 0);
+    }
+
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].iter().map(|factor| factor * magnitude).find(|step| *step >= rough).unwrap_or(10.0 * magnitude);
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    // adding zero turns a negative zero into a positive one
+    ((first..=last).map(|i| i as f64 * step + 0.0).collect(), decimals)
+}
@@ -0,0 +1,303 @@
+use super::plot::{self, Frame, Function};
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew::web_sys::{Element, WheelEvent};
+
+/// The x range shown before zooming or panning
+const DEFAULT_RANGE: (f64, f64) = (-10.0, 10.0);
+
+/// The colors of the curves, repeated if there are more curves
+const COLORS: &[&str] = &["#1e87f0", "#f0506e", "#32d296", "#faa05a", "#a05af0"];
+
+/// The smallest and largest width of the x range, beyond that the floating-point numbers get too coarse
+const MIN_SPAN: f64 = 1e-9;
+const MAX_SPAN: f64 = 1e12;
+
+pub struct Plotter {
+    link: ComponentLink<Self>,
+    functions: Vec<Function>,
+    range: (f64, f64),
+    range_error: bool,
+    curves: Vec<Vec<Option<(f64, f64)>>>,
+    frame: Frame,
+    hover: Option<f64>,
+    drag: Option<f64>,
+}
+
+pub enum PlotterMsg {
+    ChangeFrom(ChangeData),
+    ChangeTo(ChangeData),
+    Zoom(f64, f64),
+    Press(f64),
+    Move(f64),
+    Release,
+    Leave,
+    Reset,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+    #[prop_or_default]
+    pub functions: Vec<Function>,
+}
+
+impl Component for Plotter {
+    type Message = PlotterMsg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut plotter = Plotter {
+            link,
+            functions: props.functions,
+            range: DEFAULT_RANGE,
+            range_error: false,
+            curves: Vec::new(),
+            frame: Frame { x: DEFAULT_RANGE, y: (-1.0, 1.0) },
+            hover: None,
+            drag: None,
+        };
+        plotter.sample();
+        plotter
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.functions == props.functions {
+            return false;
+        }
+
+        self.functions = props.functions;
+        self.sample();
+        true
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PlotterMsg::ChangeFrom(ChangeData::Value(value)) => match value.trim().parse::<f64>() {
+                Ok(from) if self.is_span(self.range.1 - from) => self.set_range((from, self.range.1)),
+                _ => self.range_error = true,
+            },
+            PlotterMsg::ChangeTo(ChangeData::Value(value)) => match value.trim().parse::<f64>() {
+                Ok(to) if self.is_span(to - self.range.0) => self.set_range((self.range.0, to)),
+                _ => self.range_error = true,
+            },
+            PlotterMsg::ChangeFrom(_) | PlotterMsg::ChangeTo(_) => return false,
+            PlotterMsg::Zoom(factor, x) => {
+                let (min, max) = self.range;
+                if !self.is_span((max - min) * factor) {
+                    return false;
+                }
+                self.set_range((x - (x - min) * factor, x + (max - x) * factor));
+            }
+            PlotterMsg::Press(x) => {
+                self.drag = Some(x);
+                self.hover = Some(x);
+            }
+            PlotterMsg::Move(x) => match self.drag {
+                // shift the range so that the grabbed point stays under the mouse
+                Some(grabbed) => {
+                    let (min, max) = self.range;
+                    self.set_range((min + grabbed - x, max + grabbed - x));
+                }
+                None => self.hover = Some(x),
+            },
+            PlotterMsg::Release => self.drag = None,
+            PlotterMsg::Leave => {
+                self.drag = None;
+                self.hover = None;
+            }
+            PlotterMsg::Reset => self.set_range(DEFAULT_RANGE),
+        }
+        true
+    }
+
+    fn view(&self) -> Html {
+        let range = self.range;
+        let input_class = if self.range_error { "uk-input uk-form-small uk-form-width-small uk-form-danger" } else { "uk-input uk-form-small uk-form-width-small" };
+        let center = (range.0 + range.1) / 2.0;
+
+        html! {
+            <div class="uk-width-1-1">
+                <div class="uk-flex uk-flex-middle uk-flex-wrap uk-margin-small-bottom">
+                    <label class="uk-form-label uk-margin-small-right" for="plotfrom">{ "x from" }</label>
+                    <input class=input_class
+                           id="plotfrom"
+                           type="text"
+                           onchange=self.link.callback(PlotterMsg::ChangeFrom)
+                           value=range.0.to_string() />
+                    <label class="uk-form-label uk-margin-small-left uk-margin-small-right" for="plotto">{ "to" }</label>
+                    <input class=input_class
+                           id="plotto"
+                           type="text"
+                           onchange=self.link.callback(PlotterMsg::ChangeTo)
+                           value=range.1.to_string() />
+                    <div class="uk-button-group uk-margin-small-left">
+                        <button class="uk-button uk-button-small uk-button-default"
+                                type="button"
+                                uk-tooltip="zoom in, or use the mouse wheel"
+                                onclick=self.link.callback(move |_| PlotterMsg::Zoom(0.5, center))>
+                            { "+" }
+                        </button>
+                        <button class="uk-button uk-button-small uk-button-default"
+                                type="button"
+                                uk-tooltip="zoom out"
+                                onclick=self.link.callback(move |_| PlotterMsg::Zoom(2.0, center))>
+                            { "\u{2212}" }
+                        </button>
+                        <button class="uk-button uk-button-small uk-button-default"
+                                type="button"
+                                onclick=self.link.callback(|_| PlotterMsg::Reset)>
+                            { "reset" }
+                        </button>
+                    </div>
+                </div>
+                <div style=if self.drag.is_some() { "cursor: grabbing; user-select: none" } else { "cursor: crosshair; user-select: none" }
+                     onmousedown=self.link.batch_callback(move |e: MouseEvent| {
+                         e.prevent_default();
+                         x_at(range, &e).map(PlotterMsg::Press)
+                     })
+                     onmousemove=self.link.batch_callback(move |e: MouseEvent| x_at(range, &e).map(PlotterMsg::Move))
+                     onmouseup=self.link.callback(|_| PlotterMsg::Release)
+                     onmouseleave=self.link.callback(|_| PlotterMsg::Leave)
+                     onwheel=self.link.batch_callback(move |e: WheelEvent| {
+                         e.prevent_default();
+                         let factor = if e.delta_y() > 0.0 { 1.25 } else { 0.8 };
+                         x_at(range, &e).map(|x| PlotterMsg::Zoom(factor, x))
+                     })>
+                    <svg viewBox=format!("0 0 {} {}", plot::WIDTH, plot::HEIGHT) width="100%" style="display: block; pointer-events: none">
+                        { self.view_grid() }
+                        { for self.curves.iter().enumerate().map(|(index, points)| html! {
+                            <path d=self.frame.path(points) fill="none" stroke=color(index) stroke-width="2" clip-path="url(#plotarea)" />
+                        }) }
+                        { self.view_crosshair() }
+                    </svg>
+                </div>
+                { self.view_readout() }
+            </div>
+        }
+    }
+}
+
+impl Plotter {
+    /// Samples all functions over the range and fits the y range to them
+    fn sample(&mut self) {
+        self.curves = self.functions.iter().map(|function| function.sample(self.range)).collect();
+        self.frame = Frame::fit(self.range, &self.curves);
+    }
+
+    fn set_range(&mut self, range: (f64, f64)) {
+        self.range = range;
+        self.range_error = false;
+        self.sample();
+    }
+
+    fn is_span(&self, span: f64) -> bool {
+        (MIN_SPAN..=MAX_SPAN).contains(&span)
+    }
+
+    /// Creates the grid lines with their labels, the axes, and the clipping of the curves to the plot area
+    fn view_grid(&self) -> Html {
+        let (left, top, width, height) = self.frame.area();
+        let (x_ticks, x_decimals) = plot::ticks(self.frame.x);
+        let (y_ticks, y_decimals) = plot::ticks(self.frame.y);
+        let line = |x1: f64, y1: f64, x2: f64, y2: f64, opacity: &str| html! {
+            <line x1=x1.to_string() y1=y1.to_string() x2=x2.to_string() y2=y2.to_string() stroke="currentColor" stroke-opacity=opacity.to_string() />
+        };
+
+        html! {
+            <>
+                <defs>
+                    <clipPath id="plotarea">
+                        <rect x=left.to_string() y=top.to_string() width=width.to_string() height=height.to_string() />
+                    </clipPath>
+                </defs>
+                { for x_ticks.iter().map(|tick| {
+                    let x = self.frame.svg_x(*tick);
+                    html! {
+                        <>
+                            { line(x, top, x, top + height, if *tick == 0.0 { "0.6" } else { "0.15" }) }
+                            <text x=x.to_string() y=(top + height + 16.0).to_string() text-anchor="middle" font-size="11" fill="currentColor">
+                                { format!("{:.1$}", tick, x_decimals) }
+                            </text>
+                        </>
+                    }
+                }) }
+                { for y_ticks.iter().map(|tick| {
+                    let y = self.frame.svg_y(*tick);
+                    html! {
+                        <>
+                            { line(left, y, left + width, y, if *tick == 0.0 { "0.6" } else { "0.15" }) }
+                            <text x=(left - 5.0).to_string() y=(y + 4.0).to_string() text-anchor="end" font-size="11" fill="currentColor">
+                                { format!("{:.1$}", tick, y_decimals) }
+                            </text>
+                        </>
+                    }
+                }) }
+                <rect x=left.to_string() y=top.to_string() width=width.to_string() height=height.to_string() fill="none" stroke="currentColor" stroke-opacity="0.3" />
+            </>
+        }
+    }
+
+    /// Creates the vertical line at the mouse and the points where it crosses the curves
+    fn view_crosshair(&self) -> Html {
+        let x = match self.hover {
+            Some(x) if x >= self.frame.x.0 && x <= self.frame.x.1 => x,
+            _ => return html! {},
+        };
+        let (_, top, _, height) = self.frame.area();
+        let svg_x = self.frame.svg_x(x).to_string();
+
+        html! {
+            <>
+                <line x1=svg_x.clone() y1=top.to_string() x2=svg_x.clone() y2=(top + height).to_string() stroke="currentColor" stroke-opacity="0.5" stroke-dasharray="4 3" />
+                { for self.functions.iter().enumerate().filter_map(|(index, function)| {
+                    let y = function.eval(x).ok().filter(|y| y.is_finite() && *y >= self.frame.y.0 && *y <= self.frame.y.1)?;
+                    Some(html! {
+                        <circle cx=svg_x.clone() cy=self.frame.svg_y(y).to_string() r="4" fill=color(index) />
+                    })
+                }) }
+            </>
+        }
+    }
+
+    /// Creates the legend of the curves with their values at the crosshair
+    fn view_readout(&self) -> Html {
+        // two more decimals than the tick labels tell points between the grid lines apart
+        let (_, x_decimals) = plot::ticks(self.frame.x);
+        let (_, y_decimals) = plot::ticks(self.frame.y);
+        let value = |function: &Function| match (self.hover, self.drag) {
+            (Some(x), None) => match function.eval(x) {
+                Ok(y) if y.is_finite() => format!(" = {:.1$}", y, y_decimals + 2),
+                _ => " = undefined".to_string(),
+            },
+            _ => "".to_string(),
+        };
+
+        html! {
+            <div class="uk-text-small uk-margin-small-top">
+                { match (self.hover, self.drag) {
+                    (Some(x), None) => html! { <span class="uk-margin-right">{ format!("x = {:.1$}", x, x_decimals + 2) }</span> },
+                    _ => html! {},
+                } }
+                { for self.functions.iter().enumerate().map(|(index, function)| html! {
+                    <span class="uk-margin-right" style=format!("color: {}", color(index))>
+                        <code>{ function.label.clone() }</code>
+                        { value(function) }
+                    </span>
+                }) }
+            </div>
+        }
+    }
+}
+
+fn color(index: usize) -> String {
+    COLORS[index % COLORS.len()].to_string()
+}
+
+/// Returns the x value under the mouse, the SVG takes no pointer events so the offset is always within the wrapper
+fn x_at(range: (f64, f64), e: &MouseEvent) -> Option<f64> {
+    let element = e.current_target()?.dyn_into::<Element>().ok()?;
+    match element.client_width() {
+        0 => None,
+        width => Some(plot::x_at(range, e.offset_x() as f64 / width as f64)),
+    }
+}
@@ -0,0 +1,280 @@
+use crate::components::common::bitwidth;
+use crate::components::common::parse::{self, ParseError};
+use num_bigint::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::collections::BTreeMap;
+
+/// The most bits of a shift, exponent, or result accepted without a word size, to keep the numbers in memory
+const MAX_UNBOUNDED_BITS: u32 = 65536;
+
+/// The word size integer expressions wrap around to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WordSize {
+    pub width: Option<u32>,
+    pub signed: bool,
+}
+
+impl WordSize {
+    /// Wraps the value around to the word size and reads it as signed or unsigned
+    pub fn fit(&self, value: BigInt) -> BigInt {
+        match self.width {
+            Some(width) => {
+                let pattern = bitwidth::wrap(&value, width);
+                if self.signed { bitwidth::to_signed(&pattern, width) } else { pattern }
+            }
+            None => value,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(BigInt),
+    Name(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+const OPERATORS: &[&str] = &["<<", ">>", "**", "&", "|", "^", "~", "+", "-", "*", "/", "%"];
+
+/// Evaluates an integer expression with C-like operators and precedence, every intermediate result wraps around to the
+/// word size, positions in errors are shifted by the offset of the expression within its line
+pub fn evaluate(expression: &str, offset: usize, variables: &BTreeMap<String, BigInt>, size: WordSize) -> Result<BigInt, String> {
+    let tokens = tokenize(expression, offset)?;
+    let mut parser = Parser { tokens, index: 0, variables, size, end: offset + expression.chars().count() + 1 };
+
+    let value = parser.or()?;
+    match parser.tokens.get(parser.index) {
+        Some((token, position)) => Err(format!("unexpected {} at position {}, an operator may be missing", describe(token), position)),
+        None => Ok(value),
+    }
+}
+
+fn tokenize(expression: &str, offset: usize) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let position = offset + i + 1;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            let literal: String = chars[start..i].iter().collect();
+            let value = parse::parse_lenient(&literal, 10).map_err(|e| match e {
+                ParseError::InvalidDigit { digit, position: digit_position, radix } => {
+                    format!("'{}' at position {} is not a base {} digit", digit, position + digit_position - 1, radix)
+                }
+                _ => format!("invalid number '{}' at position {}", literal, position),
+            })?;
+            tokens.push((Token::Number(value), position));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Name(chars[start..i].iter().collect()), position));
+        } else if c == '(' {
+            tokens.push((Token::Open, position));
+            i += 1;
+        } else if c == ')' {
+            tokens.push((Token::Close, position));
+            i += 1;
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            match OPERATORS.iter().find(|operator| rest.starts_with(*operator)) {
+                Some(operator) => {
+                    tokens.push((Token::Operator(operator), position));
+                    i += operator.len();
+                }
+                None => return Err(format!("unexpected '{}' at position {}", c, position)),
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(value) => format!("'{}'", value),
+        Token::Name(name) => format!("'{}'", name),
+        Token::Operator(operator) => format!("'{}'", operator),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    variables: &'a BTreeMap<String, BigInt>,
+    size: WordSize,
+    end: usize,
+}
+
+impl Parser<'_> {
+    /// Consumes the next token if it is one of the operators and returns it with its position
+    fn operator(&mut self, operators: &[&str]) -> Option<(&'static str, usize)> {
+        match self.tokens.get(self.index) {
+            Some((Token::Operator(operator), position)) if operators.contains(operator) => {
+                self.index += 1;
+                Some((*operator, *position))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a left-associative chain of binary operators of the same precedence
+    fn chain(&mut self, operators: &[&str], next: fn(&mut Self) -> Result<BigInt, String>) -> Result<BigInt, String> {
+        let mut value = next(self)?;
+        while let Some((operator, position)) = self.operator(operators) {
+            let rhs = next(self)?;
+            value = self.size.fit(self.apply(operator, position, value, rhs)?);
+        }
+        Ok(value)
+    }
+
+    fn or(&mut self) -> Result<BigInt, String> {
+        self.chain(&["|"], Self::xor)
+    }
+
+    fn xor(&mut self) -> Result<BigInt, String> {
+        self.chain(&["^"], Self::and)
+    }
+
+    fn and(&mut self) -> Result<BigInt, String> {
+        self.chain(&["&"], Self::shift)
+    }
+
+    fn shift(&mut self) -> Result<BigInt, String> {
+        self.chain(&["<<", ">>"], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<BigInt, String> {
+        self.chain(&["+", "-"], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<BigInt, String> {
+        self.chain(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<BigInt, String> {
+        match self.operator(&["-", "+", "~"]) {
+            Some(("-", _)) => {
+                let value = self.unary()?;
+                Ok(self.size.fit(-value))
+            }
+            Some(("~", _)) => {
+                let value = self.unary()?;
+                Ok(self.size.fit(!value))
+            }
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<BigInt, String> {
+        let base = self.primary()?;
+        match self.operator(&["**"]) {
+            Some((operator, position)) => {
+                let exponent = self.unary()?;
+                Ok(self.size.fit(self.apply(operator, position, base, exponent)?))
+            }
+            None => Ok(base),
+        }
+    }
+
+    fn primary(&mut self) -> Result<BigInt, String> {
+        let (token, position) = match self.tokens.get(self.index) {
+            Some(token) => token.clone(),
+            None => return Err(format!("incomplete expression, a value is missing at position {}", self.end)),
+        };
+        self.index += 1;
+
+        match token {
+            Token::Number(value) => Ok(self.size.fit(value)),
+            Token::Name(name) => match self.variables.get(&name) {
+                Some(value) => Ok(self.size.fit(value.clone())),
+                None => Err(format!("unknown variable '{}' at position {}", name, position)),
+            },
+            Token::Open => {
+                let value = self.or()?;
+                match self.tokens.get(self.index) {
+                    Some((Token::Close, _)) => {
+                        self.index += 1;
+                        Ok(value)
+                    }
+                    _ => Err(format!("unclosed '(' at position {}", position)),
+                }
+            }
+            token => Err(format!("unexpected {} at position {}", describe(&token), position)),
+        }
+    }
+
+    fn apply(&self, operator: &str, position: usize, lhs: BigInt, rhs: BigInt) -> Result<BigInt, String> {
+        match operator {
+            "|" => Ok(lhs | rhs),
+            "^" => Ok(lhs ^ rhs),
+            "&" => Ok(lhs & rhs),
+            "+" => Ok(lhs + rhs),
+            "-" => Ok(lhs - rhs),
+            "*" => {
+                self.limit(operator, position, lhs.bits() + rhs.bits())?;
+                Ok(lhs * rhs)
+            }
+            "/" | "%" if rhs.is_zero() => Err(format!("division by zero at position {}", position)),
+            "/" => Ok(lhs / rhs),
+            "%" => Ok(lhs % rhs),
+            "<<" => {
+                let bits = self.bits(operator, position, &rhs)?;
+                self.limit(operator, position, lhs.bits() + bits as u64)?;
+                Ok(lhs << bits)
+            }
+            ">>" => Ok(lhs >> self.bits(operator, position, &rhs)?),
+            // with a word size only the lowest bits matter, so the power can be taken modulo 2^width
+            _ => match self.size.width {
+                Some(width) if !rhs.is_negative() => Ok(lhs.modpow(&rhs, &(BigInt::one() << width))),
+                // zero, one, and minus one stay small for any exponent
+                _ if lhs.bits() <= 1 && !rhs.is_negative() => {
+                    Ok(if rhs.is_zero() { BigInt::one() } else if rhs.bit(0) { lhs } else { lhs.abs() })
+                }
+                _ => {
+                    let exponent = self.bits(operator, position, &rhs)?;
+                    self.limit(operator, position, lhs.bits() * exponent as u64)?;
+                    Ok(lhs.pow(exponent))
+                }
+            },
+        }
+    }
+
+    /// Returns the shift distance or exponent, a shift is limited to the word size
+    fn bits(&self, operator: &str, position: usize, value: &BigInt) -> Result<u32, String> {
+        if value.is_negative() {
+            return Err(format!("negative operand of '{}' at position {}", operator, position));
+        }
+
+        match (value.to_u32(), self.size.width) {
+            // shifting by the width or more leaves only zeros or the sign
+            (bits, Some(width)) => Ok(bits.map_or(width, |bits| bits.min(width))),
+            (Some(bits), None) if bits <= MAX_UNBOUNDED_BITS => Ok(bits),
+            _ => Err(format!("operand of '{}' at position {} is too large", operator, position)),
+        }
+    }
+
+    /// Rejects a result of more bits than accepted without a word size
+    fn limit(&self, operator: &str, position: usize, bits: u64) -> Result<(), String> {
+        match self.size.width {
+            None if bits > MAX_UNBOUNDED_BITS as u64 => Err(format!("result of '{}' at position {} is too large", operator, position)),
+            _ => Ok(()),
+        }
+    }
+}
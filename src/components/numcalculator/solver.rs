@@ -0,0 +1,325 @@
+use super::statistics::{self, Value};
+use super::worksheet::{self, Line, Outcome};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The most iterations of Newton's method or bisection
+const MAX_ITERATIONS: usize = 100;
+
+/// The change of x relative to its size at which the iterations stop
+const TOLERANCE: f64 = 1e-12;
+
+/// The number of intervals each range is sampled in when looking for a sign change
+const BRACKET_SAMPLES: usize = 100;
+
+/// An equation `lhs = rhs` as the expression `(lhs) - (rhs)`, which is zero at a solution, with the numbers of the
+/// worksheet it refers to
+struct Equation {
+    line: usize,
+    expression: String,
+    constants: BTreeMap<String, f64>,
+}
+
+impl Equation {
+    /// Returns how far the sides of the equation are apart for the values of the unknowns
+    fn residual(&self, values: &BTreeMap<String, f64>) -> f64 {
+        let mut namespace = |name: &str, args: Vec<f64>| match values.get(name).or_else(|| self.constants.get(name)) {
+            Some(value) if args.is_empty() => Some(*value),
+            _ => statistics::call(name, &args).ok(),
+        };
+
+        fasteval::ez_eval(&self.expression, &mut namespace).unwrap_or(f64::NAN)
+    }
+}
+
+/// The values of the unknowns and how they were found
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    pub values: Vec<(String, f64)>,
+    pub method: String,
+}
+
+/// Evaluates the worksheet with its equations, lines with a single `=` whose sides have unknown names are equations,
+/// all of them are solved together, a single one by Newton's method or bisection and several as a linear system
+pub fn solve(text: &str) -> (Vec<Line<String>>, Option<Result<Solution, String>>) {
+    let mut variables = BTreeMap::new();
+    let mut lines = Vec::new();
+    let mut equations = Vec::new();
+    let mut unknowns = BTreeSet::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = worksheet::strip_comment(line);
+        let (name, start) = worksheet::split_assignment(line);
+        let expression = &line[start..];
+        let offset = line[..start].chars().count();
+
+        // an assignment is an equation if its value depends on unknown names
+        let equals = match (find_equals(line), &name) {
+            (Ok(Some(equals)), Some(_)) if unknowns_of(expression, offset, &variables).is_ok_and(|(_, names)| !names.is_empty()) => Some(Ok(equals)),
+            (Ok(Some(equals)), None) => Some(Ok(equals)),
+            (Err(e), _) => Some(Err(e)),
+            _ => None,
+        };
+
+        let outcome = match equals {
+            Some(equals) => match equals.and_then(|equals| parse_equation(index, line, equals, &variables)) {
+                Ok((equation, names)) => {
+                    equations.push(equation);
+                    unknowns.extend(names);
+                    lines.push(Line { name: None, outcome: Outcome::Value("equation".to_string()) });
+                    continue;
+                }
+                Err(e) => Outcome::Error(e),
+            },
+            None if expression.trim().is_empty() => Outcome::Empty,
+            None => match worksheet::eval_float(expression, offset, &mut variables) {
+                Ok(value) => {
+                    variables.insert(worksheet::ANS.to_string(), value.clone());
+                    if let Some(name) = &name {
+                        variables.insert(name.clone(), value.clone());
+                    }
+                    Outcome::Value(value.to_string())
+                }
+                Err(e) => Outcome::Error(e),
+            },
+        };
+        lines.push(Line { name, outcome });
+    }
+
+    if equations.is_empty() {
+        return (lines, None);
+    }
+
+    let unknowns: Vec<String> = unknowns.into_iter().collect();
+    let solution = solve_equations(&equations, &unknowns);
+    if let Ok(solution) = &solution {
+        let values = solution.values.iter().cloned().collect();
+        for equation in &equations {
+            let residual = equation.residual(&values);
+            let text = if residual == 0.0 { "residual 0".to_string() } else { format!("residual {:.1e}", residual) };
+            lines[equation.line].outcome = Outcome::Value(text);
+        }
+    }
+
+    (lines, Some(solution))
+}
+
+/// Returns the value rounded to 12 significant digits, hiding the rounding errors of the iterations
+pub fn round(value: f64) -> f64 {
+    // adding zero turns a negative zero into a positive one
+    format!("{:.11e}", value).parse::<f64>().unwrap_or(value) + 0.0
+}
+
+/// Returns the byte index of the `=` of an equation, comparisons like `==` or `<=` are ignored
+fn find_equals(line: &str) -> Result<Option<usize>, String> {
+    let bytes = line.as_bytes();
+    let mut found = bytes.iter().enumerate().filter(|(index, byte)| {
+        **byte == b'='
+            && !matches!(index.checked_sub(1).map(|before| bytes[before]), Some(b'=' | b'<' | b'>' | b'!'))
+            && bytes.get(index + 1) != Some(&b'=')
+    });
+
+    match (found.next(), found.next()) {
+        (Some(_), Some((index, _))) => Err(format!("second '=' at position {}, an equation has only one", line[..index].chars().count() + 1)),
+        (first, _) => Ok(first.map(|(index, _)| index)),
+    }
+}
+
+/// Splits the line at the `=` into an equation and the unknown names of its sides
+fn parse_equation(index: usize, line: &str, equals: usize, variables: &BTreeMap<String, Value>) -> Result<(Equation, BTreeSet<String>), String> {
+    let (lhs, lhs_unknowns) = unknowns_of(&line[..equals], 0, variables)?;
+    let (rhs, rhs_unknowns) = unknowns_of(&line[equals + 1..], line[..=equals].chars().count(), variables)?;
+
+    let names: BTreeSet<String> = lhs_unknowns.union(&rhs_unknowns).cloned().collect();
+    if names.is_empty() {
+        return Err("the equation has no unknowns, use '==' to compare".to_string());
+    }
+
+    let constants = variables
+        .iter()
+        .filter_map(|(name, value)| match value {
+            Value::Number(value) => Some((name.clone(), *value)),
+            _ => None,
+        })
+        .collect();
+
+    Ok((Equation { line: index, expression: format!("({}) - ({})", lhs, rhs), constants }, names))
+}
+
+/// Checks the expression and returns it with its lists expanded as well as the names that are no variables
+fn unknowns_of(expression: &str, offset: usize, variables: &BTreeMap<String, Value>) -> Result<(String, BTreeSet<String>), String> {
+    let (expanded, origins) = statistics::expand_lists(expression, variables)
+        .map_err(|index| format!("a list at position {} can only be a function argument", offset + index + 1))?;
+
+    let mut names = BTreeSet::new();
+    let mut failed = None;
+    let mut namespace = |name: &str, args: Vec<f64>| match variables.get(name) {
+        Some(Value::Number(value)) if args.is_empty() => Some(*value),
+        None if args.is_empty() => {
+            names.insert(name.to_string());
+            Some(1.0)
+        }
+        _ => statistics::call(name, &args).map_err(|e| failed = Some(e)).ok(),
+    };
+
+    // only errors matter here, the value is that of all unknowns being one
+    match fasteval::ez_eval(&expanded, &mut namespace) {
+        Ok(_) => Ok((expanded, names)),
+        Err(e) => Err(worksheet::describe(&expanded, &origins, offset, e, failed)),
+    }
+}
+
+fn solve_equations(equations: &[Equation], unknowns: &[String]) -> Result<Solution, String> {
+    match (equations.len(), unknowns.len()) {
+        (1, 1) => find_root(&equations[0], &unknowns[0]),
+        (count, unknown_count) if count == unknown_count => solve_linear(equations, unknowns),
+        (count, unknown_count) => Err(format!(
+            "{} {} for {} {} {}, the counts need to match",
+            count,
+            if count == 1 { "equation" } else { "equations" },
+            unknown_count,
+            if unknown_count == 1 { "unknown" } else { "unknowns" },
+            unknowns.join(", ")
+        )),
+    }
+}
+
+/// Finds a root of a single equation with Newton's method, or by bisection if that does not converge
+fn find_root(equation: &Equation, name: &str) -> Result<Solution, String> {
+    let f = |x: f64| equation.residual(&BTreeMap::from([(name.to_string(), x)]));
+
+    match newton(f).or_else(|| bisection(f)) {
+        Some((x, method)) => Ok(Solution { values: vec![(name.to_string(), x)], method }),
+        None => Err(format!("no solution for {} found, Newton's method did not converge and the equation changes sign nowhere", name)),
+    }
+}
+
+/// Runs Newton's method from 1 with a numerical derivative
+fn newton(f: impl Fn(f64) -> f64) -> Option<(f64, String)> {
+    let mut x = 1.0;
+
+    for iteration in 1..=MAX_ITERATIONS {
+        let y = f(x);
+        if y == 0.0 {
+            return Some((x, format!("Newton's method converged in {} iterations", iteration)));
+        }
+
+        let h = 1e-6 * f64::max(1.0, x.abs());
+        let slope = (f(x + h) - f(x - h)) / (2.0 * h);
+        if !y.is_finite() || !slope.is_finite() || slope == 0.0 {
+            return None;
+        }
+
+        let next = x - y / slope;
+        if (next - x).abs() <= TOLERANCE * f64::max(1.0, next.abs()) {
+            return f(next).is_finite().then(|| (next, format!("Newton's method converged in {} iterations", iteration)));
+        }
+        x = next;
+    }
+
+    None
+}
+
+/// Looks for a sign change in growing ranges around zero and narrows it down by bisection, a sign change at a pole is
+/// no root
+fn bisection(f: impl Fn(f64) -> f64) -> Option<(f64, String)> {
+    let (mut low, mut high) = bracket(&f)?;
+    let limit = f64::max(f(low).abs(), f(high).abs());
+
+    for iteration in 1..=MAX_ITERATIONS {
+        let middle = (low + high) / 2.0;
+        let y = f(middle);
+        if y == 0.0 || (high - low) / 2.0 <= TOLERANCE * f64::max(1.0, middle.abs()) {
+            return (y.abs() <= limit).then(|| (middle, format!("bisection converged in {} iterations", iteration)));
+        }
+
+        if y.signum() == f(low).signum() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    None
+}
+
+/// Returns the first interval with a sign change in the ranges from -1 to 1 up to -1e9 to 1e9
+fn bracket(f: impl Fn(f64) -> f64) -> Option<(f64, f64)> {
+    (0..=9).find_map(|exponent| {
+        let scale = 10f64.powi(exponent);
+        let points: Vec<(f64, f64)> = (0..=BRACKET_SAMPLES)
+            .map(|i| -scale + 2.0 * scale * i as f64 / BRACKET_SAMPLES as f64)
+            .map(|x| (x, f(x)))
+            .filter(|(_, y)| y.is_finite())
+            .collect();
+
+        points.windows(2).find(|pair| pair[0].1.signum() != pair[1].1.signum()).map(|pair| (pair[0].0, pair[1].0))
+    })
+}
+
+/// Solves linear equations by Gaussian elimination with partial pivoting, the coefficients are read off the residuals
+/// at zero and along each unknown
+fn solve_linear(equations: &[Equation], unknowns: &[String]) -> Result<Solution, String> {
+    let n = unknowns.len();
+    let residuals = |point: &[f64]| -> Vec<f64> {
+        let values = unknowns.iter().cloned().zip(point.iter().copied()).collect();
+        equations.iter().map(|equation| equation.residual(&values)).collect()
+    };
+    let not_linear = || format!("the equations are not linear in {}, only a single equation can be nonlinear", unknowns.join(", "));
+
+    // each row holds the coefficients and the constant on the right-hand side
+    let origin = residuals(&vec![0.0; n]);
+    let mut rows: Vec<Vec<f64>> = origin.iter().map(|constant| {
+        let mut row = vec![0.0; n];
+        row.push(-constant);
+        row
+    }).collect();
+    for j in 0..n {
+        let mut point = vec![0.0; n];
+        point[j] = 1.0;
+        for (row, (residual, constant)) in rows.iter_mut().zip(residuals(&point).iter().zip(&origin)) {
+            row[j] = residual - constant;
+        }
+    }
+    if rows.iter().flatten().any(|value| !value.is_finite()) {
+        return Err(not_linear());
+    }
+
+    // a linear system has the residuals its coefficients predict at any other point
+    let probe: Vec<f64> = (0..n).map(|j| 1.5 + 0.75 * j as f64).collect();
+    for (row, actual) in rows.iter().zip(residuals(&probe)) {
+        let predicted = row.iter().zip(&probe).map(|(coefficient, x)| coefficient * x).sum::<f64>() - row[n];
+        let close = (actual - predicted).abs() <= 1e-9 * (1.0 + actual.abs() + predicted.abs());
+        if !close {
+            return Err(not_linear());
+        }
+    }
+
+    let scale = rows.iter().flat_map(|row| &row[..n]).fold(0.0, |max: f64, value| max.max(value.abs()));
+    for column in 0..n {
+        let pivot = (column..n).max_by(|a, b| rows[*a][column].abs().total_cmp(&rows[*b][column].abs())).unwrap_or(column);
+        if rows[pivot][column].abs() <= 1e-12 * scale {
+            return Err("the equations have no unique solution, some of them depend on each other or contradict".to_string());
+        }
+        rows.swap(column, pivot);
+
+        let (done, rest) = rows.split_at_mut(column + 1);
+        let pivot_row = &done[column];
+        for row in rest {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot_value) in row.iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut solution = vec![0.0; n];
+    for (column, row) in rows.iter().enumerate().rev() {
+        let known: f64 = row[column + 1..n].iter().zip(&solution[column + 1..]).map(|(coefficient, x)| coefficient * x).sum();
+        solution[column] = (row[n] - known) / row[column];
+    }
+
+    Ok(Solution {
+        values: unknowns.iter().cloned().zip(solution).collect(),
+        method: format!("Gaussian elimination of {} linear equations", n),
+    })
+}
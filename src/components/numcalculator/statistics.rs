@@ -0,0 +1,195 @@
+use super::plot::Function;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The names of the functions over lists
+pub const FUNCTIONS: &[&str] = &["mean", "median", "stddev", "percentile", "sum", "count", "min", "max"];
+
+/// A value of the standard mode, a number, a list of numbers, or a function of `x` to plot
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    List(Vec<f64>),
+    Function(Function),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Number(value) => write!(f, "{}", value),
+            Value::List(values) => {
+                let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Value::Function(_) => write!(f, "plotted"),
+        }
+    }
+}
+
+/// Calls a list function, `percentile` takes the percentile as last argument, or tells why it has no result, like
+/// `mean` all of them need at least one value
+pub fn call(name: &str, args: &[f64]) -> Result<f64, String> {
+    let result = match name {
+        "mean" => mean(args),
+        "median" => percentile(args, 50.0),
+        "stddev" => stddev(args),
+        "percentile" => match args.split_last() {
+            Some((p, _)) if !(0.0..=100.0).contains(p) => return Err("percentile must be between 0 and 100".to_string()),
+            Some((p, values)) => percentile(values, *p),
+            None => None,
+        },
+        "sum" | "count" if args.is_empty() => None,
+        "sum" => Some(args.iter().sum()),
+        "count" => Some(args.len() as f64),
+        _ => return Err(format!("unknown function '{}'", name)),
+    };
+
+    result.ok_or_else(|| format!("{} needs more values", name))
+}
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Returns the sample standard deviation
+pub fn stddev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+
+    let mean = mean(values)?;
+    let squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    Some((squares / (values.len() - 1) as f64).sqrt())
+}
+
+/// Returns the percentile from 0 to 100, interpolating linearly between the closest ranks
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    if values.is_empty() || !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+/// Returns the summary of the samples as rows of name and value
+pub fn summary(values: &[f64]) -> Vec<(&'static str, Option<f64>)> {
+    vec![
+        ("count", Some(values.len() as f64)),
+        ("sum", Some(values.iter().sum())),
+        ("min", values.iter().copied().reduce(f64::min)),
+        ("max", values.iter().copied().reduce(f64::max)),
+        ("mean", mean(values)),
+        ("stddev", stddev(values)),
+        ("p50", percentile(values, 50.0)),
+        ("p90", percentile(values, 90.0)),
+        ("p95", percentile(values, 95.0)),
+        ("p99", percentile(values, 99.0)),
+    ]
+}
+
+/// Parses pasted numbers separated by line breaks, commas, semicolons, or whitespace
+pub fn parse_samples(text: &str) -> Result<Vec<f64>, String> {
+    let mut values = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        for item in line.split(|c: char| c == ',' || c == ';' || c.is_whitespace()).filter(|item| !item.is_empty()) {
+            match item.parse::<f64>() {
+                Ok(value) => values.push(value),
+                Err(_) => return Err(format!("'{}' in line {} is no number", item, index + 1)),
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Splits the expression at commas outside of parentheses and brackets into items with their byte offsets
+pub fn split_list(expression: &str) -> Vec<(usize, &str)> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, c) in expression.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push((start, &expression[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    items.push((start, &expression[start..]));
+    items
+}
+
+/// Writes list literals and list variables used as function arguments as plain arguments, e.g. `mean(samples)` as
+/// `mean(12, 15, 20)`, with the index of the character in the expression each character and the end come from, or
+/// returns the position of a list that is no function argument
+pub fn expand_lists(expression: &str, variables: &BTreeMap<String, Value>) -> Result<(String, Vec<usize>), usize> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut expanded = String::new();
+    let mut origins = Vec::new();
+    let mut push = |text: &str, origin: usize, step: usize| {
+        for (index, c) in text.chars().enumerate() {
+            expanded.push(c);
+            origins.push(origin + index * step);
+        }
+    };
+    let mut i = 0;
+
+    // a list is an argument if it directly follows an opening parenthesis or a comma and is followed by one of them
+    let is_argument = |start: usize, end: usize| {
+        let before = chars[..start].iter().rev().find(|c| !c.is_whitespace());
+        let after = chars[end..].iter().find(|c| !c.is_whitespace());
+        matches!(before, Some('(' | ',')) && matches!(after, Some(')' | ','))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '[' {
+            let end = match chars[i..].iter().position(|c| *c == ']') {
+                Some(length) => i + length + 1,
+                None => return Err(i),
+            };
+            if !is_argument(i, end) {
+                return Err(i);
+            }
+            push(&chars[i + 1..end - 1].iter().collect::<String>(), i + 1, 1);
+            i = end;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            let name: String = chars[start..i].iter().collect();
+            match variables.get(&name) {
+                Some(Value::List(values)) if is_argument(start, i) => {
+                    let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                    push(&values.join(", "), start, 0);
+                }
+                Some(Value::List(_)) => return Err(start),
+                _ => push(&name, start, 1),
+            }
+        } else {
+            push(&c.to_string(), i, 1);
+            i += 1;
+        }
+    }
+
+    origins.push(chars.len());
+    Ok((expanded, origins))
+}
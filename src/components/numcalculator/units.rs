@@ -0,0 +1,537 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The exponents of the base dimensions length, time, data, and temperature
+type Dimension = [i32; 4];
+
+const NONE: Dimension = [0, 0, 0, 0];
+const LENGTH: Dimension = [1, 0, 0, 0];
+const TIME: Dimension = [0, 1, 0, 0];
+const DATA: Dimension = [0, 0, 1, 0];
+const TEMPERATURE: Dimension = [0, 0, 0, 1];
+const RATE: Dimension = [0, -1, 1, 0];
+const FREQUENCY: Dimension = [0, -1, 0, 0];
+
+/// The names of the base units a result without a matching unit is shown in, data is shown in bytes
+const BASE_NAMES: [&str; 4] = ["m", "s", "B", "K"];
+const BASE_FACTORS: [f64; 4] = [1.0, 1.0, 8.0, 1.0];
+
+/// The prefixes a unit accepts
+#[derive(Clone, Copy, PartialEq)]
+enum Prefixes {
+    None,
+    Si,
+    SiAndIec,
+}
+
+/// A unit's name, its factor to the base units, its dimension, its offset for temperatures, and its prefixes
+const UNITS: &[(&str, f64, Dimension, f64, Prefixes)] = &[
+    ("m", 1.0, LENGTH, 0.0, Prefixes::Si),
+    ("meter", 1.0, LENGTH, 0.0, Prefixes::None),
+    ("meters", 1.0, LENGTH, 0.0, Prefixes::None),
+    ("inch", 0.0254, LENGTH, 0.0, Prefixes::None),
+    ("inches", 0.0254, LENGTH, 0.0, Prefixes::None),
+    ("ft", 0.3048, LENGTH, 0.0, Prefixes::None),
+    ("foot", 0.3048, LENGTH, 0.0, Prefixes::None),
+    ("feet", 0.3048, LENGTH, 0.0, Prefixes::None),
+    ("yd", 0.9144, LENGTH, 0.0, Prefixes::None),
+    ("mi", 1609.344, LENGTH, 0.0, Prefixes::None),
+    ("mile", 1609.344, LENGTH, 0.0, Prefixes::None),
+    ("miles", 1609.344, LENGTH, 0.0, Prefixes::None),
+    ("s", 1.0, TIME, 0.0, Prefixes::Si),
+    ("sec", 1.0, TIME, 0.0, Prefixes::None),
+    ("second", 1.0, TIME, 0.0, Prefixes::None),
+    ("seconds", 1.0, TIME, 0.0, Prefixes::None),
+    ("min", 60.0, TIME, 0.0, Prefixes::None),
+    ("minute", 60.0, TIME, 0.0, Prefixes::None),
+    ("minutes", 60.0, TIME, 0.0, Prefixes::None),
+    ("h", 3600.0, TIME, 0.0, Prefixes::None),
+    ("hour", 3600.0, TIME, 0.0, Prefixes::None),
+    ("hours", 3600.0, TIME, 0.0, Prefixes::None),
+    ("d", 86400.0, TIME, 0.0, Prefixes::None),
+    ("day", 86400.0, TIME, 0.0, Prefixes::None),
+    ("days", 86400.0, TIME, 0.0, Prefixes::None),
+    ("week", 604800.0, TIME, 0.0, Prefixes::None),
+    ("weeks", 604800.0, TIME, 0.0, Prefixes::None),
+    ("year", 31557600.0, TIME, 0.0, Prefixes::None),
+    ("years", 31557600.0, TIME, 0.0, Prefixes::None),
+    ("Hz", 1.0, FREQUENCY, 0.0, Prefixes::Si),
+    ("bit", 1.0, DATA, 0.0, Prefixes::SiAndIec),
+    ("bits", 1.0, DATA, 0.0, Prefixes::None),
+    ("b", 1.0, DATA, 0.0, Prefixes::SiAndIec),
+    ("B", 8.0, DATA, 0.0, Prefixes::SiAndIec),
+    ("byte", 8.0, DATA, 0.0, Prefixes::None),
+    ("bytes", 8.0, DATA, 0.0, Prefixes::None),
+    ("bps", 1.0, RATE, 0.0, Prefixes::Si),
+    ("K", 1.0, TEMPERATURE, 0.0, Prefixes::None),
+    ("°C", 1.0, TEMPERATURE, 273.15, Prefixes::None),
+    ("degC", 1.0, TEMPERATURE, 273.15, Prefixes::None),
+    ("°F", 5.0 / 9.0, TEMPERATURE, 459.67 * 5.0 / 9.0, Prefixes::None),
+    ("degF", 5.0 / 9.0, TEMPERATURE, 459.67 * 5.0 / 9.0, Prefixes::None),
+];
+
+const SI_PREFIXES: &[(&str, f64)] = &[
+    ("k", 1e3), ("M", 1e6), ("G", 1e9), ("T", 1e12), ("P", 1e15), ("E", 1e18),
+    ("m", 1e-3), ("u", 1e-6), ("µ", 1e-6), ("n", 1e-9), ("p", 1e-12),
+];
+
+const IEC_PREFIXES: &[(&str, f64)] = &[
+    ("Ki", 1024.0), ("Mi", 1048576.0), ("Gi", 1073741824.0), ("Ti", 1099511627776.0), ("Pi", 1125899906842624.0),
+    ("Ei", 1152921504606846976.0),
+];
+
+/// A unit, possibly compound like `Mbit/s`, with its factor and offset to the base units
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    name: String,
+    factor: f64,
+    dimension: Dimension,
+    offset: f64,
+}
+
+impl Unit {
+    /// Looks up a unit by its name, which may start with an SI or IEC prefix
+    fn find(name: &str) -> Option<Unit> {
+        let unit = |(unit, factor, dimension, offset, _): &(&str, f64, Dimension, f64, Prefixes)| Unit {
+            name: unit.to_string(),
+            factor: *factor,
+            dimension: *dimension,
+            offset: *offset,
+        };
+
+        if let Some(found) = UNITS.iter().find(|(unit, ..)| *unit == name) {
+            return Some(unit(found));
+        }
+
+        let prefixed = |prefixes: &[(&str, f64)], accepted: &[Prefixes]| {
+            prefixes.iter().find_map(|(prefix, prefix_factor)| {
+                let rest = name.strip_prefix(prefix)?;
+                let found = UNITS.iter().find(|(unit, .., prefixes)| *unit == rest && accepted.contains(prefixes))?;
+                Some(Unit { name: name.to_string(), factor: prefix_factor * found.1, ..unit(found) })
+            })
+        };
+
+        prefixed(IEC_PREFIXES, &[Prefixes::SiAndIec]).or_else(|| prefixed(SI_PREFIXES, &[Prefixes::Si, Prefixes::SiAndIec]))
+    }
+
+    fn combine(&self, other: &Unit, operator: char) -> Unit {
+        let sign = if operator == '/' { -1 } else { 1 };
+        let name = if other.name.contains(['/', '*']) { format!("({})", other.name) } else { other.name.clone() };
+
+        Unit {
+            name: format!("{}{}{}", self.name, operator, name),
+            factor: if sign < 0 { self.factor / other.factor } else { self.factor * other.factor },
+            dimension: add(self.dimension, other.dimension, sign),
+            offset: 0.0,
+        }
+    }
+
+    fn power(&self, exponent: i32) -> Unit {
+        Unit {
+            name: format!("{}^{}", self.name, exponent),
+            factor: self.factor.powi(exponent),
+            dimension: self.dimension.map(|d| d * exponent),
+            offset: 0.0,
+        }
+    }
+}
+
+/// A value in base units with the unit it is shown in
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantity {
+    value: f64,
+    dimension: Dimension,
+    unit: Option<Unit>,
+}
+
+impl Quantity {
+    fn number(value: f64) -> Quantity {
+        Quantity { value, dimension: NONE, unit: None }
+    }
+
+    fn of(value: f64, unit: Unit) -> Quantity {
+        Quantity { value: value * unit.factor + unit.offset, dimension: unit.dimension, unit: Some(unit) }
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} {}", format_number((self.value - unit.offset) / unit.factor), unit.name),
+            None if self.dimension == NONE => write!(f, "{}", format_number(self.value)),
+            None => {
+                let factor: f64 = (0..4).map(|i| BASE_FACTORS[i].powi(self.dimension[i])).product();
+                write!(f, "{} {}", format_number(self.value / factor), base_name(self.dimension))
+            }
+        }
+    }
+}
+
+/// Formats a number with up to 10 significant digits, very large or small numbers in scientific notation
+fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if !(1e-6..1e15).contains(&value.abs()) {
+        return format!("{:e}", value);
+    }
+
+    let rounded = format!("{:.*}", (9 - value.abs().log10().floor() as i32).max(0) as usize, value);
+    if rounded.contains('.') {
+        rounded.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        rounded
+    }
+}
+
+/// Returns the name of the dimension in base units, e.g. `B/s`
+fn base_name(dimension: Dimension) -> String {
+    let part = |i: usize, exponent: i32| match exponent {
+        1 => BASE_NAMES[i].to_string(),
+        e => format!("{}^{}", BASE_NAMES[i], e),
+    };
+    let numerator: Vec<String> = (0..4).filter(|i| dimension[*i] > 0).map(|i| part(i, dimension[i])).collect();
+    let denominator: Vec<String> = (0..4).filter(|i| dimension[*i] < 0).map(|i| part(i, -dimension[i])).collect();
+
+    match (numerator.is_empty(), denominator.is_empty()) {
+        (_, true) => numerator.join("*"),
+        (true, false) => format!("1/{}", denominator.join("*")),
+        (false, false) => format!("{}/{}", numerator.join("*"), denominator.join("*")),
+    }
+}
+
+fn add(a: Dimension, b: Dimension, sign: i32) -> Dimension {
+    [a[0] + sign * b[0], a[1] + sign * b[1], a[2] + sign * b[2], a[3] + sign * b[3]]
+}
+
+/// A product or quotient of units with a single base dimension is shown in the base unit, e.g. `GiB / (Mbit/s)` in
+/// seconds
+fn is_simple(dimension: Dimension) -> bool {
+    dimension.iter().map(|d| d.abs()).sum::<i32>() <= 1 && dimension.iter().all(|d| *d >= 0)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+}
+
+fn tokenize(expression: &str, offset: usize) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let position = offset + i + 1;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == '_') {
+                i += 1;
+            }
+            // an exponent needs digits, so that e.g. `2 EiB` stays a unit
+            if i + 1 < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let sign = if chars[i + 1] == '-' || chars[i + 1] == '+' { 1 } else { 0 };
+                if chars.get(i + 1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+
+            let literal: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            match literal.parse::<f64>() {
+                Ok(value) => tokens.push((Token::Number(value), position)),
+                Err(_) => return Err(format!("invalid number '{}' at position {}", literal, position)),
+            }
+        } else if c.is_alphabetic() || c == '°' || c == '_' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Name(chars[start..i].iter().collect()), position));
+        } else if "+-*/^()·".contains(c) {
+            tokens.push((Token::Operator(if c == '·' { '*' } else { c }), position));
+            i += 1;
+        } else {
+            return Err(format!("unexpected '{}' at position {}", c, position));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Evaluates an expression of quantities with units, like `1.5 GiB / 200 Mbit/s in minutes`, positions in errors are
+/// shifted by the offset of the expression within its line
+pub fn evaluate(expression: &str, offset: usize, variables: &BTreeMap<String, Quantity>) -> Result<Quantity, String> {
+    let tokens = tokenize(expression, offset)?;
+    let mut parser = Parser { tokens, index: 0, variables, end: offset + expression.chars().count() + 1 };
+
+    let mut value = parser.sum()?;
+    if let Some((Token::Name(name), position)) = parser.peek() {
+        if name == "in" || name == "to" {
+            parser.index += 1;
+            let unit = parser.unit_term(true)?;
+            if unit.dimension != value.dimension {
+                return Err(format!("cannot convert {} to {} at position {}", describe(&value), unit.name, position));
+            }
+            value.unit = Some(unit);
+        }
+    }
+
+    match parser.peek() {
+        Some((Token::Name(name), position)) => Err(format!("unknown unit or variable '{}' at position {}", name, position)),
+        Some((token, position)) => Err(format!("unexpected {} at position {}, an operator may be missing", describe_token(&token), position)),
+        None => Ok(value),
+    }
+}
+
+/// Describes the unit of a quantity for error messages
+fn describe(quantity: &Quantity) -> String {
+    match &quantity.unit {
+        Some(unit) => unit.name.clone(),
+        None if quantity.dimension == NONE => "a number".to_string(),
+        None => base_name(quantity.dimension),
+    }
+}
+
+/// Returns the unit of a quantity if it is a temperature on a scale with an offset, like °C
+fn offset_unit(quantity: &Quantity) -> Option<&Unit> {
+    quantity.unit.as_ref().filter(|unit| unit.offset != 0.0)
+}
+
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Number(value) => format!("'{}'", value),
+        Token::Name(name) => format!("'{}'", name),
+        Token::Operator(operator) => format!("'{}'", operator),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    variables: &'a BTreeMap<String, Quantity>,
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<(Token, usize)> {
+        self.tokens.get(self.index).cloned()
+    }
+
+    /// Consumes the next token if it is one of the operators and returns it with its position
+    fn operator(&mut self, operators: &str) -> Option<(char, usize)> {
+        match self.peek() {
+            Some((Token::Operator(operator), position)) if operators.contains(operator) => {
+                self.index += 1;
+                Some((operator, position))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the unit the name refers to unless it is a variable
+    fn unit(&self, name: &str) -> Option<Unit> {
+        if self.variables.contains_key(name) { None } else { Unit::find(name) }
+    }
+
+    fn sum(&mut self) -> Result<Quantity, String> {
+        let mut value = self.product()?;
+        while let Some((operator, position)) = self.operator("+-") {
+            let rhs = self.product()?;
+            if rhs.dimension != value.dimension {
+                return Err(format!("cannot {} {} and {} at position {}", if operator == '+' { "add" } else { "subtract" },
+                                   describe(&value), describe(&rhs), position));
+            }
+
+            // temperatures on a scale with an offset are absolute, so the difference of two is shown in kelvin, and a
+            // difference added to or subtracted from one has to be given in kelvin
+            let difference = operator == '-' && offset_unit(&value).is_some() && offset_unit(&rhs).is_some();
+            if let Some(unit) = offset_unit(&rhs).filter(|_| !difference) {
+                return Err(format!("cannot {} {} at position {}, give a temperature difference in K",
+                                   if operator == '+' { "add" } else { "subtract" }, unit.name, position));
+            }
+
+            value.value += if operator == '+' { rhs.value } else { -rhs.value };
+            if difference {
+                value.unit = Unit::find("K");
+            } else if value.unit.is_none() {
+                value.unit = rhs.unit;
+            }
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<Quantity, String> {
+        let mut value = self.unary()?;
+        while let Some((operator, position)) = self.operator("*/") {
+            let rhs = self.unary()?;
+            if let Some(unit) = offset_unit(&value).or(offset_unit(&rhs)) {
+                return Err(format!("cannot {} {} at position {}, convert the temperature to K first",
+                                   if operator == '*' { "multiply" } else { "divide" }, unit.name, position));
+            }
+            if operator == '/' && rhs.value == 0.0 {
+                return Err(format!("division by zero at position {}", position));
+            }
+
+            let sign = if operator == '/' { -1 } else { 1 };
+            let dimension = add(value.dimension, rhs.dimension, sign);
+            let unit = match (&value.unit, &rhs.unit) {
+                (Some(lhs), Some(rhs)) if !is_simple(dimension) => Some(lhs.combine(rhs, operator)),
+                (Some(lhs), None) if rhs.dimension == NONE => Some(lhs.clone()),
+                (None, Some(rhs)) if value.dimension == NONE && operator == '*' => Some(rhs.clone()),
+                _ => None,
+            };
+
+            value = Quantity {
+                value: if operator == '/' { value.value / rhs.value } else { value.value * rhs.value },
+                dimension,
+                unit,
+            };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Quantity, String> {
+        match self.operator("-+") {
+            Some(('-', _)) => {
+                let mut value = self.unary()?;
+                // a temperature like -40 °F is negated on its own scale
+                let offset = offset_unit(&value).map_or(0.0, |unit| unit.offset);
+                value.value = 2.0 * offset - value.value;
+                Ok(value)
+            }
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Quantity, String> {
+        let base = self.primary()?;
+        let position = match self.operator("^") {
+            Some((_, position)) => position,
+            None => return Ok(base),
+        };
+
+        if let Some(unit) = offset_unit(&base) {
+            return Err(format!("cannot raise {} at position {}, convert the temperature to K first", unit.name, position));
+        }
+
+        let exponent = self.unary()?;
+        if exponent.dimension != NONE {
+            return Err(format!("exponent at position {} must be a number", position + 1));
+        }
+        if base.dimension == NONE {
+            return Ok(Quantity::number(base.value.powf(exponent.value)));
+        }
+        if exponent.value.fract() != 0.0 {
+            return Err(format!("exponent of a quantity with unit at position {} must be an integer", position + 1));
+        }
+
+        let exponent = exponent.value as i32;
+        Ok(Quantity {
+            value: base.value.powi(exponent),
+            dimension: base.dimension.map(|d| d * exponent),
+            unit: base.unit.map(|unit| unit.power(exponent)),
+        })
+    }
+
+    fn primary(&mut self) -> Result<Quantity, String> {
+        let (token, position) = match self.peek() {
+            Some(token) => token,
+            None => return Err(format!("incomplete expression, a value is missing at position {}", self.end)),
+        };
+        self.index += 1;
+
+        match token {
+            Token::Number(value) => match self.peek() {
+                Some((Token::Name(name), _)) if self.unit(&name).is_some() => Ok(Quantity::of(value, self.unit_term(false)?)),
+                _ => Ok(Quantity::number(value)),
+            },
+            Token::Name(name) => {
+                if let Some(value) = self.variables.get(&name) {
+                    return Ok(value.clone());
+                }
+                match Unit::find(&name) {
+                    Some(unit) => Ok(Quantity::of(1.0, unit)),
+                    None => Err(format!("unknown unit or variable '{}' at position {}", name, position)),
+                }
+            }
+            Token::Operator('(') => {
+                let value = self.sum()?;
+                match self.operator(")") {
+                    Some(_) => Ok(value),
+                    None => Err(format!("unclosed '(' at position {}", position)),
+                }
+            }
+            token => Err(format!("unexpected {} at position {}", describe_token(&token), position)),
+        }
+    }
+
+    /// Parses a unit following a number or conversion like `Mbit/s` or `m^2`, a `/` or `*` only belongs to the unit if
+    /// a unit follows it, and units in parentheses are only read after a conversion
+    fn unit_term(&mut self, conversion: bool) -> Result<Unit, String> {
+        let mut unit = self.unit_factor(conversion)?;
+
+        loop {
+            let next_is_unit = match (self.tokens.get(self.index), self.tokens.get(self.index + 1)) {
+                (Some((Token::Operator('/' | '*'), _)), Some((Token::Name(name), _))) => self.unit(name).is_some(),
+                (Some((Token::Operator('/' | '*'), _)), Some((Token::Operator('('), _))) => conversion,
+                _ => false,
+            };
+            if !next_is_unit {
+                return Ok(unit);
+            }
+
+            let (operator, _) = self.operator("/*").unwrap_or(('*', 0));
+            unit = unit.combine(&self.unit_factor(conversion)?, operator);
+        }
+    }
+
+    fn unit_factor(&mut self, conversion: bool) -> Result<Unit, String> {
+        let (token, position) = match self.peek() {
+            Some(token) => token,
+            None => return Err(format!("incomplete expression, a unit is missing at position {}", self.end)),
+        };
+        self.index += 1;
+
+        let unit = match token {
+            Token::Name(name) => match Unit::find(&name) {
+                Some(unit) => unit,
+                None => return Err(format!("unknown unit '{}' at position {}", name, position)),
+            },
+            Token::Operator('(') if conversion => {
+                let unit = self.unit_term(true)?;
+                if self.operator(")").is_none() {
+                    return Err(format!("unclosed '(' at position {}", position));
+                }
+                Unit { name: format!("({})", unit.name), ..unit }
+            }
+            token => return Err(format!("expected a unit instead of {} at position {}", describe_token(&token), position)),
+        };
+
+        match self.operator("^") {
+            Some((_, position)) if unit.offset != 0.0 => {
+                Err(format!("cannot raise {} at position {}, convert the temperature to K first", unit.name, position))
+            }
+            Some((_, position)) => {
+                let sign = if self.operator("-").is_some() { -1 } else { 1 };
+                match self.peek() {
+                    Some((Token::Number(exponent), _)) if exponent.fract() == 0.0 => {
+                        self.index += 1;
+                        Ok(unit.power(sign * exponent as i32))
+                    }
+                    _ => Err(format!("exponent of a unit at position {} must be an integer", position + 1)),
+                }
+            }
+            None => Ok(unit),
+        }
+    }
+}
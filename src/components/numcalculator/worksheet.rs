@@ -0,0 +1,179 @@
+use super::message;
+use super::plot::{self, Function};
+use super::statistics::{self, Value};
+use std::collections::BTreeMap;
+
+/// The name of the variable holding the result of the previous line
+pub const ANS: &str = "ans";
+
+/// The outcome of a single worksheet line
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome<T> {
+    Empty,
+    Value(T),
+    Error(String),
+}
+
+/// A worksheet line with the variable it assigns to, if any
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line<T> {
+    pub name: Option<String>,
+    pub outcome: Outcome<T>,
+}
+
+impl<T> Line<T> {
+    /// Converts the value of the line, e.g. to format it
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Line<U> {
+        let outcome = match self.outcome {
+            Outcome::Empty => Outcome::Empty,
+            Outcome::Value(value) => Outcome::Value(f(value)),
+            Outcome::Error(e) => Outcome::Error(e),
+        };
+
+        Line { name: self.name, outcome }
+    }
+}
+
+/// Evaluates the worksheet line by line, a line may assign its result to a name which later lines can use, as well as
+/// `ans` for the last result, the expression of a line is evaluated with its offset within the line and the variables
+pub fn evaluate<T: Clone>(
+    text: &str,
+    mut eval: impl FnMut(&str, usize, &mut BTreeMap<String, T>) -> Result<T, String>,
+) -> Vec<Line<T>> {
+    let mut variables = BTreeMap::new();
+
+    text.lines()
+        .map(|line| {
+            let line = strip_comment(line);
+            let (name, start) = split_assignment(line);
+            let expression = &line[start..];
+            if expression.trim().is_empty() {
+                return Line { name, outcome: Outcome::Empty };
+            }
+
+            let outcome = match eval(expression, line[..start].chars().count(), &mut variables) {
+                Ok(value) => {
+                    variables.insert(ANS.to_string(), value.clone());
+                    if let Some(name) = &name {
+                        variables.insert(name.clone(), value.clone());
+                    }
+                    Outcome::Value(value)
+                }
+                Err(e) => Outcome::Error(e),
+            };
+
+            Line { name, outcome }
+        })
+        .collect()
+}
+
+/// Returns the last line that is not empty with its index
+pub fn last<T>(lines: &[Line<T>]) -> Option<(usize, &Outcome<T>)> {
+    lines.iter().enumerate().rev().map(|(index, line)| (index, &line.outcome)).find(|(_, outcome)| !matches!(outcome, Outcome::Empty))
+}
+
+/// Evaluates a floating-point expression with fasteval or a list of them, separated by commas and optionally enclosed
+/// in brackets, results that are no finite number are errors, an expression of an undefined `x` is a function to plot
+pub fn eval_float(expression: &str, offset: usize, variables: &mut BTreeMap<String, Value>) -> Result<Value, String> {
+    let trimmed = expression.trim();
+    let items = statistics::split_list(expression);
+
+    let list = if let Some(value @ (Value::List(_) | Value::Function(_))) = variables.get(trimmed) {
+        return Ok(value.clone());
+    } else if items.len() > 1 {
+        items
+    } else if trimmed.starts_with('[') && trimmed.ends_with(']') {
+        let start = expression.find('[').unwrap_or(0) + 1;
+        let end = expression.rfind(']').unwrap_or(expression.len());
+        statistics::split_list(&expression[start..end]).into_iter().map(|(index, item)| (start + index, item)).collect()
+    } else if !variables.contains_key(plot::VARIABLE) && message::find_name(expression, plot::VARIABLE).is_some() {
+        return eval_function(expression, offset, variables).map(Value::Function);
+    } else {
+        return eval_number(expression, offset, variables).map(Value::Number);
+    };
+
+    list.into_iter()
+        .filter(|(_, item)| !item.trim().is_empty())
+        .map(|(index, item)| eval_number(item, offset + expression[..index].chars().count(), variables))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::List)
+}
+
+fn eval_number(expression: &str, offset: usize, variables: &BTreeMap<String, Value>) -> Result<f64, String> {
+    let (expanded, origins) = expand_lists(expression, offset, variables)?;
+    let mut failed = None;
+    let mut namespace = |name: &str, args: Vec<f64>| match variables.get(name) {
+        Some(Value::Number(value)) if args.is_empty() => Some(*value),
+        _ => statistics::call(name, &args).map_err(|e| failed = Some(e)).ok(),
+    };
+
+    match fasteval::ez_eval(&expanded, &mut namespace) {
+        Ok(value) => match message::describe_value(value) {
+            Some(e) => Err(e),
+            None => Ok(value),
+        },
+        Err(e) => Err(describe(&expanded, &origins, offset, e, failed)),
+    }
+}
+
+/// Captures the expression of `x` with the numbers it refers to, it is evaluated once to report errors early
+fn eval_function(expression: &str, offset: usize, variables: &BTreeMap<String, Value>) -> Result<Function, String> {
+    let constants = variables
+        .iter()
+        .filter_map(|(name, value)| match value {
+            Value::Number(value) => Some((name.clone(), *value)),
+            _ => None,
+        })
+        .collect();
+    let (expanded, origins) = expand_lists(expression, offset, variables)?;
+    let function = Function { label: expression.trim().to_string(), expression: expanded, constants };
+
+    // results that are no finite number are just gaps in the plot
+    match function.eval(1.0) {
+        Ok(_) => Ok(function),
+        Err(e) => Err(describe(&function.expression, &origins, offset, e, None)),
+    }
+}
+
+fn expand_lists(expression: &str, offset: usize, variables: &BTreeMap<String, Value>) -> Result<(String, Vec<usize>), String> {
+    statistics::expand_lists(expression, variables)
+        .map_err(|index| format!("a list at position {} can only be a function argument", offset + index + 1))
+}
+
+/// Describes the error of an expression with its lists expanded, a list function that has no result tells why if it
+/// was called
+pub fn describe(expression: &str, origins: &[usize], offset: usize, error: fasteval::Error, failed: Option<String>) -> String {
+    match (error, failed) {
+        (fasteval::Error::Undefined(name), failed) if statistics::FUNCTIONS.contains(&name.as_str()) => {
+            failed.unwrap_or_else(|| format!("{} needs more values", name))
+        }
+        (e, _) => message::describe(expression, origins, offset, &e),
+    }
+}
+
+/// Removes a `#` comment from the end of the line
+pub fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(index) => &line[..index],
+        None => line,
+    }
+}
+
+/// Splits `name = expression` into the name and the start of the expression, comparisons like `==` or `<=` are no
+/// assignments
+pub fn split_assignment(line: &str) -> (Option<String>, usize) {
+    if let Some(index) = line.find('=') {
+        let name = line[..index].trim();
+        if !line[index + 1..].starts_with('=') && is_identifier(name) {
+            return (Some(name.to_string()), index + 1);
+        }
+    }
+
+    (None, 0)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -1,5 +1,13 @@
+mod alphabet;
+mod bitfield;
+
+use crate::components::common::{bitwidth, parse};
 use crate::components::copytoclipboard::CopyToClipboard;
 
+use self::alphabet::Encoding;
+use self::bitfield::BitField;
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
 use yew::prelude::*;
 
 pub struct NumConverter {
@@ -8,6 +16,18 @@ pub struct NumConverter {
     dec: String,
     oct: String,
     bin: String,
+    custom: String,
+    encoding: Encoding,
+    value: Option<BigInt>,
+    width: Option<u32>,
+    signed: bool,
+    grouping: bool,
+    error: Option<(Field, String)>,
+    fields_input: String,
+    fields: Vec<BitField>,
+    fields_error: String,
+    field_input: Option<(usize, String)>,
+    field_error: Option<(usize, String)>,
 }
 
 pub enum NumConverterMsg {
@@ -15,6 +35,25 @@ pub enum NumConverterMsg {
     ConvertDec(String),
     ConvertOct(String),
     ConvertBin(String),
+    ConvertCustom(String),
+    ChangeEncoding(usize),
+    ChangeWidth(Option<u32>),
+    ChangeSigned(bool),
+    ToggleGrouping,
+    ToggleBit(u32),
+    SwapBytes,
+    ChangeFields(String),
+    ChangeFieldValue(usize, String),
+}
+
+/// The fields showing the value in different numeral systems
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Hex,
+    Dec,
+    Oct,
+    Bin,
+    Custom,
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -34,6 +73,18 @@ impl Component for NumConverter {
             dec: "".to_string(),
             oct: "".to_string(),
             bin: "".to_string(),
+            custom: "".to_string(),
+            encoding: Encoding::Radix(36),
+            value: None,
+            width: None,
+            signed: true,
+            grouping: false,
+            error: None,
+            fields_input: "".to_string(),
+            fields: Vec::new(),
+            fields_error: "".to_string(),
+            field_input: None,
+            field_error: None,
         }
     }
 
@@ -43,130 +94,493 @@ impl Component for NumConverter {
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
-            NumConverterMsg::ConvertHex(value) => {
-                match i64::from_str_radix(&value, 16) {
-                    Ok(v) => {
-                        self.dec = format!("{}", v);
-                        self.oct = format!("{:o}", v);
-                        self.bin = format!("{:b}", v);
-                    }
-                    Err(_) => {
-                        self.dec.clear();
-                        self.oct.clear();
-                        self.bin.clear();
-                    }
+            NumConverterMsg::ConvertHex(value) => self.convert(Field::Hex, value),
+            NumConverterMsg::ConvertDec(value) => self.convert(Field::Dec, value),
+            NumConverterMsg::ConvertOct(value) => self.convert(Field::Oct, value),
+            NumConverterMsg::ConvertBin(value) => self.convert(Field::Bin, value),
+            NumConverterMsg::ConvertCustom(value) => self.convert(Field::Custom, value),
+            NumConverterMsg::ChangeEncoding(index) => {
+                if let Some(encoding) = Encoding::all().get(index) {
+                    self.encoding = *encoding;
+                    self.calc_fields(None);
                 }
-
-                self.hex = value;
             }
-            NumConverterMsg::ConvertDec(value) => {
-                match value.parse::<i64>() {
-                    Ok(v) => {
-                        self.hex = format!("{:X}", v);
-                        self.oct = format!("{:o}", v);
-                        self.bin = format!("{:b}", v);
-                    }
-                    Err(_) => {
-                        self.hex.clear();
-                        self.oct.clear();
-                        self.bin.clear();
-                    }
-                }
-
-                self.dec = value;
+            NumConverterMsg::ChangeWidth(width) => {
+                self.width = width;
+                self.value = self.value.as_ref().map(|v| self.interpret(v));
+                self.calc_fields(None);
             }
-            NumConverterMsg::ConvertOct(value) => {
-                match i64::from_str_radix(&value, 8) {
-                    Ok(v) => {
-                        self.hex = format!("{:X}", v);
-                        self.dec = format!("{}", v);
-                        self.bin = format!("{:b}", v);
-                    }
-                    Err(_) => {
-                        self.hex.clear();
-                        self.dec.clear();
-                        self.bin.clear();
+            NumConverterMsg::ChangeSigned(signed) => {
+                self.signed = signed;
+                self.value = self.value.as_ref().map(|v| self.interpret(v));
+                self.calc_fields(None);
+            }
+            NumConverterMsg::ToggleGrouping => {
+                self.grouping = !self.grouping;
+                self.calc_fields(None);
+            }
+            NumConverterMsg::ToggleBit(bit) => {
+                let mut pattern = self.pattern();
+                pattern.set_bit(bit as u64, !pattern.bit(bit as u64));
+                self.value = Some(self.read_pattern(&pattern));
+                self.calc_fields(None);
+            }
+            NumConverterMsg::SwapBytes => {
+                let pattern = bitwidth::swap_bytes(&self.pattern(), self.grid_width());
+                self.value = Some(self.read_pattern(&pattern));
+                self.calc_fields(None);
+            }
+            NumConverterMsg::ChangeFields(value) => {
+                match bitfield::parse_fields(&value) {
+                    Ok(fields) => {
+                        self.fields = fields;
+                        self.fields_error.clear();
                     }
+                    Err(e) => self.fields_error = e,
                 }
 
-                self.oct = value;
+                self.fields_input = value;
+                self.field_input = None;
+                self.field_error = None;
             }
-            NumConverterMsg::ConvertBin(value) => {
-                match i64::from_str_radix(&value, 2) {
+            NumConverterMsg::ChangeFieldValue(index, value) => {
+                let field = match self.fields.get(index) {
+                    Some(field) => field,
+                    None => return false,
+                };
+
+                match parse::parse_lenient(&value, 10) {
                     Ok(v) => {
-                        self.hex = format!("{:X}", v);
-                        self.dec = format!("{}", v);
-                        self.oct = format!("{:o}", v);
-                    }
-                    Err(_) => {
-                        self.hex.clear();
-                        self.dec.clear();
-                        self.oct.clear();
+                        let pattern = field.insert(&self.pattern(), &v);
+                        self.value = Some(self.read_pattern(&pattern));
+                        self.calc_fields(None);
                     }
+                    Err(parse::ParseError::Empty) => self.field_error = None,
+                    Err(e) => self.field_error = Some((index, e.to_string())),
                 }
 
-                self.bin = value;
+                self.field_input = Some((index, value));
             }
         }
         true
     }
 
     fn view(&self) -> Html {
+        let grouping_class = if self.grouping { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
         html! {
             <form class="uk-grid-small" uk-grid="">
+                <div class="uk-width-1-1">
+                    <div class="uk-button-group uk-margin-small-right">
+                        { self.width_button("\u{221e}", None) }
+                        { for bitwidth::WIDTHS.iter().map(|width| self.width_button(&width.to_string(), Some(*width))) }
+                    </div>
+                    <div class="uk-button-group uk-margin-small-right">
+                        { self.signed_button("signed", true) }
+                        { self.signed_button("unsigned", false) }
+                    </div>
+                    <button class=grouping_class
+                            type="button"
+                            uk-tooltip="separate the output digits into groups"
+                            onclick=self.link.callback(|_| NumConverterMsg::ToggleGrouping)>
+                        { "1 000" }
+                    </button>
+                </div>
                 <div class="uk-width-1-6">
                     <label class="uk-form-label" for="hex">{ "Hexadecimal" }</label>
                     <div class="uk-form-controls">
                         <div class="uk-inline">
                             <CopyToClipboard from="hex" />
-                            <input class="uk-input uk-form-large mousetrap"
+                            <input class=self.input_class(Field::Hex, "uk-input uk-form-large mousetrap")
                                    id="hex"
                                    type="text"
                                    oninput=self.link.callback(|d: InputData| NumConverterMsg::ConvertHex(d.value))
                                    value=self.hex.clone() />
                         </div>
                     </div>
+                    { self.view_error(Field::Hex) }
                 </div>
                 <div class="uk-width-1-6">
                     <label class="uk-form-label" for="dec">{ "Decimal" }</label>
                     <div class="uk-form-controls">
                         <div class="uk-inline">
                             <CopyToClipboard from="dec" />
-                            <input class="uk-input uk-form-large"
+                            <input class=self.input_class(Field::Dec, "uk-input uk-form-large")
                                 id="dec"
                                 type="text"
                                 oninput=self.link.callback(|d: InputData| NumConverterMsg::ConvertDec(d.value))
                                 value=self.dec.clone() />
                         </div>
                     </div>
+                    { self.view_error(Field::Dec) }
                 </div>
                 <div class="uk-width-1-6">
                     <label class="uk-form-label" for="oct">{ "Octal" }</label>
                     <div class="uk-form-controls">
                         <div class="uk-inline">
                             <CopyToClipboard from="oct" />
-                            <input class="uk-input uk-form-large"
+                            <input class=self.input_class(Field::Oct, "uk-input uk-form-large")
                                    id="oct"
                                    type="text"
                                    oninput=self.link.callback(|data: InputData| NumConverterMsg::ConvertOct(data.value))
                                    value=self.oct.clone() />
                         </div>
                     </div>
+                    { self.view_error(Field::Oct) }
                 </div>
                 <div class="uk-width-1-2">
                     <label class="uk-form-label" for="bin">{ "Binary" }</label>
                     <div class="uk-form-controls">
                         <div class="uk-inline uk-width-expand">
                             <CopyToClipboard from="bin" />
-                            <input class="uk-input uk-form-large"
+                            <input class=self.input_class(Field::Bin, "uk-input uk-form-large")
                                 id="bin"
                                 type="text"
                                 oninput=self.link.callback(|data: InputData| NumConverterMsg::ConvertBin(data.value))
                                 value=self.bin.clone() />
                         </div>
                     </div>
+                    { self.view_error(Field::Bin) }
+                </div>
+                <div class="uk-width-1-6">
+                    <label class="uk-form-label" for="encoding">{ "Encoding" }</label>
+                    <div class="uk-form-controls">
+                        <select class="uk-select uk-form-large"
+                                id="encoding"
+                                onchange=self.link.callback(|e: ChangeData| match e {
+                                    ChangeData::Select(select) => NumConverterMsg::ChangeEncoding(select.selected_index() as usize),
+                                    _ => NumConverterMsg::ChangeEncoding(0),
+                                })>
+                            { for Encoding::all().iter().map(|encoding| html! {
+                                <option selected=*encoding == self.encoding>{ encoding.name() }</option>
+                            }) }
+                        </select>
+                    </div>
+                </div>
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label" for="custom">{ self.encoding.name() }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="custom" />
+                            <input class=self.input_class(Field::Custom, "uk-input uk-form-large")
+                                id="custom"
+                                type="text"
+                                oninput=self.link.callback(|data: InputData| NumConverterMsg::ConvertCustom(data.value))
+                                value=self.custom.clone() />
+                        </div>
+                    </div>
+                    { self.view_error(Field::Custom) }
+                </div>
+                <div class="uk-width-1-1">
+                    { self.view_bits() }
+                </div>
+                { self.view_bytes() }
+                <div class="uk-width-1-3">
+                    <label class="uk-form-label" for="bitfields">{ "Bit Fields" }</label>
+                    <div class="uk-form-controls">
+                        <textarea class="uk-textarea"
+                                  id="bitfields"
+                                  rows="4"
+                                  placeholder="mode 4..7, flag 3"
+                                  oninput=self.link.callback(|d: InputData| NumConverterMsg::ChangeFields(d.value))
+                                  value=self.fields_input.clone() />
+                    </div>
+                    <div class="uk-text-danger">{ self.fields_error.clone() }</div>
+                </div>
+                <div class="uk-width-2-3">
+                    <table class="uk-table uk-table-small uk-table-divider uk-margin-remove">
+                        <thead>
+                            <tr>
+                                <th>{ "Field" }</th>
+                                <th>{ "Bits" }</th>
+                                <th>{ "Value" }</th>
+                                <th>{ "Decimal" }</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            { for self.fields.iter().enumerate().map(|(index, field)| self.view_field(index, field)) }
+                        </tbody>
+                    </table>
                 </div>
             </form>
         }
     }
-}
\ No newline at end of file
+}
+
+impl NumConverter {
+    /// Reads the input of a field, keeping the other fields and pointing out the error if it is no valid number
+    fn convert(&mut self, field: Field, value: String) {
+        let parsed = match field {
+            Field::Hex => parse::parse_lenient(&value, 16),
+            Field::Dec => parse::parse_lenient(&value, 10),
+            Field::Oct => parse::parse_lenient(&value, 8),
+            Field::Bin => parse::parse_lenient(&value, 2),
+            Field::Custom => self.encoding.decode(&value),
+        };
+
+        match field {
+            Field::Hex => self.hex = value,
+            Field::Dec => self.dec = value,
+            Field::Oct => self.oct = value,
+            Field::Bin => self.bin = value,
+            Field::Custom => self.custom = value,
+        }
+
+        match parsed {
+            Ok(v) => {
+                self.value = Some(self.interpret(&v));
+                self.error = None;
+                self.calc_fields(Some(field));
+            }
+            Err(parse::ParseError::Empty) => {
+                self.value = None;
+                self.error = None;
+                self.calc_fields(Some(field));
+            }
+            Err(e) => self.error = Some((field, e.to_string())),
+        }
+    }
+
+    /// Wraps the value around to the selected bit width and reads it as signed or unsigned
+    fn interpret(&self, value: &BigInt) -> BigInt {
+        match self.width {
+            Some(width) => {
+                let pattern = bitwidth::wrap(value, width);
+                if self.signed { bitwidth::to_signed(&pattern, width) } else { pattern }
+            }
+            None => value.clone(),
+        }
+    }
+
+    /// Reads a pattern of the grid's width back, keeping the sign of a negative value if no bit width is selected
+    fn read_pattern(&self, pattern: &BigInt) -> BigInt {
+        match self.width {
+            None if self.value.as_ref().is_some_and(|v| v.is_negative()) => bitwidth::to_signed(pattern, self.grid_width()),
+            _ => self.interpret(pattern),
+        }
+    }
+
+    /// Formats the value in the extra field's encoding, as two's complement pattern if a bit width is selected
+    fn format_custom(&self, value: &BigInt) -> String {
+        match self.width {
+            Some(width) => self.encoding.encode(&bitwidth::wrap(value, width)),
+            None => self.encoding.encode(value),
+        }
+    }
+
+    /// Returns the number of bits shown in the grid, the selected width or the value's length rounded up to bytes
+    fn grid_width(&self) -> u32 {
+        match self.width {
+            Some(width) => width,
+            None => {
+                let value_bits = self.value.as_ref().map_or(0, |v| v.bits() + v.is_negative() as u64) as u32;
+                let field_bits = self.fields.iter().map(|field| field.high + 1).max().unwrap_or(0);
+                value_bits.max(field_bits).max(1).div_ceil(8) * 8
+            }
+        }
+    }
+
+    /// Returns the current value as unsigned bit pattern of the grid's width
+    fn pattern(&self) -> BigInt {
+        let value = self.value.clone().unwrap_or_else(BigInt::zero);
+        bitwidth::wrap(&value, self.grid_width())
+    }
+
+    /// Formats the value in the given radix, as zero-padded two's complement pattern if a bit width is selected
+    fn format(&self, value: &BigInt, radix: u32) -> String {
+        let digits = match self.width {
+            Some(width) if radix != 10 => {
+                let pattern = bitwidth::wrap(value, width).to_str_radix(radix).to_uppercase();
+                format!("{:0>1$}", pattern, bitwidth::digits(width, radix))
+            }
+            _ => value.to_str_radix(radix).to_uppercase(),
+        };
+
+        match (self.grouping, radix) {
+            (false, _) => digits,
+            (true, 10) => parse::group(&digits, 3, ','),
+            (true, 8) => parse::group(&digits, 3, ' '),
+            (true, _) => parse::group(&digits, 4, ' '),
+        }
+    }
+
+    /// Writes the current value to all fields but the one the user is typing in
+    fn calc_fields(&mut self, source: Option<Field>) {
+        let value = self.value.clone();
+        let format = |radix: u32| value.as_ref().map(|v| self.format(v, radix)).unwrap_or_default();
+        let (hex, dec, oct, bin) = (format(16), format(10), format(8), format(2));
+        let custom = value.as_ref().map(|v| self.format_custom(v)).unwrap_or_default();
+
+        if source != Some(Field::Hex)    { self.hex = hex; }
+        if source != Some(Field::Dec)    { self.dec = dec; }
+        if source != Some(Field::Oct)    { self.oct = oct; }
+        if source != Some(Field::Bin)    { self.bin = bin; }
+        if source != Some(Field::Custom) { self.custom = custom; }
+        if source.is_none()              { self.error = None; }
+        self.field_input = None;
+        self.field_error = None;
+    }
+
+    /// Returns the classes of a field's input, marked as invalid if the field has an error
+    fn input_class(&self, field: Field, class: &str) -> String {
+        match &self.error {
+            Some((error_field, _)) if *error_field == field => format!("{} uk-form-danger", class),
+            _ => class.to_string(),
+        }
+    }
+
+    /// Creates the error message below a field if its input is invalid
+    fn view_error(&self, field: Field) -> Html {
+        match &self.error {
+            Some((error_field, error)) if *error_field == field => html! {
+                <div class="uk-text-danger uk-text-small">{ error }</div>
+            },
+            _ => html! {},
+        }
+    }
+
+    /// Creates the grid of clickable bits grouped into bytes and nibbles, most significant bit first
+    fn view_bits(&self) -> Html {
+        let pattern = self.pattern();
+        let bytes = self.grid_width() / 8;
+
+        html! {
+            <div class="uk-flex uk-flex-wrap">
+                { for (0..bytes).rev().map(|byte| html! {
+                    <div class="uk-flex uk-margin-right uk-margin-small-bottom">
+                        <div class="uk-flex uk-margin-small-right">
+                            { for (4..8).rev().map(|i| self.view_bit(&pattern, byte * 8 + i)) }
+                        </div>
+                        <div class="uk-flex">
+                            { for (0..4).rev().map(|i| self.view_bit(&pattern, byte * 8 + i)) }
+                        </div>
+                    </div>
+                }) }
+            </div>
+        }
+    }
+
+    /// Creates the big-endian and little-endian byte sequences of the value and the swap bytes button
+    fn view_bytes(&self) -> Html {
+        let bytes = match self.value {
+            Some(_) => bitwidth::to_bytes(&self.pattern(), self.grid_width()),
+            None => Vec::new(),
+        };
+
+        html! {
+            <>
+                <div class="uk-width-2-5">
+                    <label class="uk-form-label" for="bigendian">{ "Bytes (Big-Endian)" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="bigendian" />
+                            <input class="uk-input"
+                                   id="bigendian"
+                                   type="text"
+                                   value=bitwidth::format_bytes(bytes.iter())
+                                   disabled=true />
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-2-5">
+                    <label class="uk-form-label" for="littleendian">{ "Bytes (Little-Endian)" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-inline uk-width-expand">
+                            <CopyToClipboard from="littleendian" />
+                            <input class="uk-input"
+                                   id="littleendian"
+                                   type="text"
+                                   value=bitwidth::format_bytes(bytes.iter().rev())
+                                   disabled=true />
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-1-5">
+                    <label class="uk-form-label" for="swapbytes">{ '\u{00a0}' }</label>
+                    <div class="uk-form-controls">
+                        <button class="uk-button uk-button-default"
+                                type="button"
+                                id="swapbytes"
+                                uk-tooltip="reverse the byte order of the value"
+                                onclick=self.link.callback(|_| NumConverterMsg::SwapBytes)>
+                            { "Swap Bytes" }
+                        </button>
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    /// Creates a single clickable bit labelled with its position
+    fn view_bit(&self, pattern: &BigInt, bit: u32) -> Html {
+        let set = pattern.bit(bit as u64);
+        let class = if set { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <div class="uk-text-center">
+                <div class="uk-text-meta uk-text-small">{ bit }</div>
+                <button class=class
+                        type="button"
+                        style="padding: 0; width: 24px"
+                        onclick=self.link.callback(move |_| NumConverterMsg::ToggleBit(bit))>
+                    { set as u8 }
+                </button>
+            </div>
+        }
+    }
+
+    /// Creates a table row of a bit field with an input to set the field's value
+    fn view_field(&self, index: usize, field: &BitField) -> Html {
+        let value = field.extract(&self.pattern());
+        // the field the user is typing in keeps the input until another change writes all fields
+        let input = match &self.field_input {
+            Some((input_index, input)) if *input_index == index => input.clone(),
+            _ => format!("0x{:X}", value),
+        };
+        let (class, error) = match &self.field_error {
+            Some((error_index, error)) if *error_index == index => ("uk-input uk-form-small uk-form-danger", error.clone()),
+            _ => ("uk-input uk-form-small", "".to_string()),
+        };
+
+        html! {
+            <tr>
+                <td>{ field.name.clone() }</td>
+                <td>{ format!("{}..{}", field.low, field.high) }</td>
+                <td>
+                    <input class=class
+                           type="text"
+                           oninput=self.link.callback(move |d: InputData| NumConverterMsg::ChangeFieldValue(index, d.value))
+                           value=input />
+                    <div class="uk-text-danger uk-text-small">{ error }</div>
+                </td>
+                <td>{ value.to_string() }</td>
+            </tr>
+        }
+    }
+
+    /// Creates a button of the bit width selection
+    fn width_button(&self, text: &str, width: Option<u32>) -> Html {
+        let class = if self.width == width { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| NumConverterMsg::ChangeWidth(width))>
+                { text }
+            </button>
+        }
+    }
+
+    /// Creates a button of the signed or unsigned selection
+    fn signed_button(&self, text: &str, signed: bool) -> Html {
+        let class = if self.signed == signed { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| NumConverterMsg::ChangeSigned(signed))>
+                { text }
+            </button>
+        }
+    }
+}
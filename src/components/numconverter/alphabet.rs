@@ -0,0 +1,117 @@
+use crate::components::common::parse::{self, ParseError};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+const BASE36: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE62: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const CROCKFORD32: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// A numeral system of the extra field, either a standard radix or a custom alphabet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Radix(u32),
+    Base58,
+    Base62,
+    Crockford32,
+}
+
+impl Encoding {
+    /// Returns all selectable encodings, the standard radixes 2 to 36 first
+    pub fn all() -> Vec<Encoding> {
+        (2..=36)
+            .map(Encoding::Radix)
+            .chain([Encoding::Base58, Encoding::Base62, Encoding::Crockford32])
+            .collect()
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Encoding::Radix(radix) => format!("Base {}", radix),
+            Encoding::Base58 => "Base58 (Bitcoin)".to_string(),
+            Encoding::Base62 => "Base62".to_string(),
+            Encoding::Crockford32 => "Crockford Base32".to_string(),
+        }
+    }
+
+    fn alphabet(&self) -> &'static str {
+        match self {
+            Encoding::Radix(radix) => &BASE36[..*radix as usize],
+            Encoding::Base58 => BASE58,
+            Encoding::Base62 => BASE62,
+            Encoding::Crockford32 => CROCKFORD32,
+        }
+    }
+
+    pub fn encode(&self, value: &BigInt) -> String {
+        if let Encoding::Radix(radix) = self {
+            return value.to_str_radix(*radix).to_uppercase();
+        }
+
+        let alphabet = self.alphabet().as_bytes();
+        let base = BigInt::from(alphabet.len());
+        let mut rest = value.abs();
+        let mut digits = Vec::new();
+
+        while !rest.is_zero() {
+            let digit = (&rest % &base).to_usize().unwrap_or(0);
+            digits.push(alphabet[digit]);
+            rest /= &base;
+        }
+
+        if digits.is_empty() {
+            digits.push(alphabet[0]);
+        }
+        if value.is_negative() {
+            digits.push(b'-');
+        }
+
+        digits.iter().rev().map(|d| *d as char).collect()
+    }
+
+    pub fn decode(&self, value: &str) -> Result<BigInt, ParseError> {
+        let alphabet = match self {
+            Encoding::Radix(radix) => return parse::parse_lenient(value, *radix),
+            _ => self.alphabet(),
+        };
+
+        let trimmed = value.trim_start();
+        let offset = value.chars().count() - trimmed.chars().count();
+        let (negative, digits, offset) = match trimmed.trim_end().strip_prefix('-') {
+            Some(digits) => (true, digits, offset + 1),
+            None => (false, trimmed.trim_end(), offset),
+        };
+
+        if value.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+        if digits.is_empty() {
+            return Err(ParseError::NoDigits);
+        }
+
+        let base = BigInt::from(alphabet.len());
+        let mut result = BigInt::zero();
+
+        for (position, c) in digits.chars().enumerate() {
+            let mapped = match self {
+                // Crockford's decoding is case-insensitive, maps look-alikes, and ignores hyphens
+                Encoding::Crockford32 => match c.to_ascii_uppercase() {
+                    '-' => continue,
+                    'O' => '0',
+                    'I' | 'L' => '1',
+                    c => c,
+                },
+                _ => c,
+            };
+
+            let digit = alphabet.find(mapped).ok_or(ParseError::InvalidDigit {
+                digit: c,
+                position: offset + position + 1,
+                radix: alphabet.len() as u32,
+            })?;
+            result = result * &base + digit;
+        }
+
+        Ok(if negative { -result } else { result })
+    }
+}
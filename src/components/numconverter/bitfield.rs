@@ -0,0 +1,70 @@
+use num_bigint::BigInt;
+use num_traits::One;
+
+/// A named range of bits from the lowest to the highest bit position, both inclusive
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitField {
+    pub name: String,
+    pub low: u32,
+    pub high: u32,
+}
+
+impl BitField {
+    pub fn width(&self) -> u32 {
+        self.high - self.low + 1
+    }
+
+    fn mask(&self) -> BigInt {
+        (BigInt::one() << self.width()) - 1
+    }
+
+    /// Returns the value of the field's bits
+    pub fn extract(&self, pattern: &BigInt) -> BigInt {
+        (pattern >> self.low) & self.mask()
+    }
+
+    /// Returns the pattern with the field's bits replaced by the given value truncated to the field's width
+    pub fn insert(&self, pattern: &BigInt, value: &BigInt) -> BigInt {
+        let cleared = pattern & !(self.mask() << self.low);
+        cleared | ((value & self.mask()) << self.low)
+    }
+}
+
+/// Parses bit fields like `4..7`, `7:4`, `4-7`, or a single bit `3` with an optional leading name, separated by
+/// new lines or commas
+pub fn parse_fields(input: &str) -> Result<Vec<BitField>, String> {
+    let mut fields = Vec::new();
+
+    for item in input.split(['\n', ',']).map(str::trim).filter(|item| !item.is_empty()) {
+        let (name, range) = match item.rsplit_once(char::is_whitespace) {
+            Some((name, range)) => (name.trim().to_string(), range),
+            None => ("".to_string(), item),
+        };
+
+        let bounds: Vec<&str> = if range.contains("..") {
+            range.split("..").collect()
+        } else {
+            range.split([':', '-']).collect()
+        };
+
+        let bounds: Vec<u32> = match bounds.iter().map(|bound| bound.parse::<u32>()).collect() {
+            Ok(bounds) => bounds,
+            Err(_) => return Err(format!("{}: invalid bit range", item)),
+        };
+
+        let (low, high) = match bounds[..] {
+            [bit] => (bit, bit),
+            [a, b] => (a.min(b), a.max(b)),
+            _ => return Err(format!("{}: invalid bit range", item)),
+        };
+
+        if high >= 1024 {
+            return Err(format!("{}: bit position too large", item));
+        }
+
+        let name = if name.is_empty() { format!("bits {}..{}", low, high) } else { name };
+        fields.push(BitField { name, low, high });
+    }
+
+    Ok(fields)
+}
@@ -1,12 +1,20 @@
+mod epoch;
+mod zones;
+
 use crate::components::copytoclipboard::CopyToClipboard;
 
 use chrono::prelude::*;
 use chrono::Utc;
-use std::time::{UNIX_EPOCH, Duration};
+use chrono_tz::Tz;
+use self::epoch::Unit;
 use wasm_bindgen::prelude::*;
+use yew::format::Json;
 use yew::prelude::*;
+use yew::services::storage::{Area, StorageService};
 
 const DATETIMEFORMATS: &'static [&'static str] = &["%Y-%m-%dT%H:%M:%SZ", "%Y-%m-%d %H:%M:%S", "%a, %e %b %Y %T"];
+const TIME_ZONE_KEY: &str = "timestamp_zone";
+const TIME_ZONES_KEY: &str = "timestamp_zones";
 
 #[wasm_bindgen(module = "/toolbox.js")]
 extern "C" {
@@ -15,9 +23,21 @@ extern "C" {
 
 pub struct TimestampConverter {
     link: ComponentLink<Self>,
+    storage: Result<StorageService, &'static str>,
     epoch: String,
+    epoch_error: Option<String>,
+    unit: Option<Unit>,
+    detected: Unit,
     human: String,
+    human_error: Option<String>,
     format: String,
+    instant: Option<DateTime<Utc>>,
+    note: Option<String>,
+    zone: Tz,
+    zone_error: bool,
+    zones: Vec<Tz>,
+    new_zone: String,
+    new_zone_error: bool,
 }
 
 pub enum TimestampConverterMsg {
@@ -25,6 +45,11 @@ pub enum TimestampConverterMsg {
     ConvertHuman(String),
     ChangeFormat(String),
     SetNow,
+    ChangeUnit(Option<Unit>),
+    ChangeZone(ChangeData),
+    ChangeNewZone(String),
+    AddZone,
+    RemoveZone(usize),
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -36,11 +61,37 @@ impl Component for TimestampConverter {
     type Properties = Props;
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let storage = StorageService::new(Area::Local);
+        let (zone, zones) = match &storage {
+            Ok(storage) => {
+                let zone = if let Json(Ok(zone)) = storage.restore::<Json<Result<String, _>>>(TIME_ZONE_KEY) { zones::find(&zone) } else { None };
+                let zones = if let Json(Ok(zones)) = storage.restore::<Json<Result<Vec<String>, _>>>(TIME_ZONES_KEY) {
+                    zones.iter().filter_map(|zone| zones::find(zone)).collect()
+                } else {
+                    Vec::new()
+                };
+                (zone.unwrap_or(Tz::UTC), zones)
+            },
+            Err(_) => (Tz::UTC, Vec::new()),
+        };
+
         TimestampConverter {
             link: link,
+            storage,
             epoch: "".to_string(),
+            epoch_error: None,
+            unit: None,
+            detected: Unit::Seconds,
             human: "".to_string(),
+            human_error: None,
             format: DATETIMEFORMATS[0].to_string(),
+            instant: None,
+            note: None,
+            zone,
+            zone_error: false,
+            zones,
+            new_zone: "".to_string(),
+            new_zone_error: false,
         }
     }
 
@@ -51,41 +102,84 @@ impl Component for TimestampConverter {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             TimestampConverterMsg::ConvertEpoch(value) => {
-                if let Ok(secs) =  value.parse::<u64>() {
-                    let ts = UNIX_EPOCH + Duration::from_secs(secs);
-                    let dt = DateTime::<Utc>::from(ts);
-                    self.human = dt.format(&self.format).to_string();
-                }
-
-                self.epoch = value;
+                self.convert_epoch(value);
             }
             TimestampConverterMsg::ConvertHuman(value) => {
-                match NaiveDateTime::parse_from_str(&value, &self.format) {
-                    Ok(dt) => self.epoch = dt.timestamp().to_string(),
-                    Err(_) => {}
+                match self.parse_human(&value) {
+                    Ok((dt, note)) => {
+                        self.instant = Some(dt);
+                        self.epoch = epoch::format(&dt, self.unit.unwrap_or(self.detected));
+                        self.epoch_error = None;
+                        self.human_error = None;
+                        self.note = note;
+                    }
+                    Err(_) if value.trim().is_empty() => {
+                        self.human_error = None;
+                        self.note = None;
+                    }
+                    Err(e) => {
+                        self.human_error = Some(e);
+                        self.note = None;
+                    }
                 }
 
                 self.human = value;
             }
             TimestampConverterMsg::ChangeFormat(format) => {
                 self.format = format;
-
-                if let Ok(secs) =  self.epoch.parse::<u64>() {
-                    let ts = UNIX_EPOCH + Duration::from_secs(secs);
-                    let dt = DateTime::<Utc>::from(ts);
-                    self.human = dt.format(&self.format).to_string();
-                    self.epoch = dt.timestamp().to_string();
-                }
+                self.format_human();
             }
             TimestampConverterMsg::SetNow => {
                 match DateTime::parse_from_rfc2822(&getCurrentUTC()) {
                     Ok(dt) => {
-                        self.epoch = dt.timestamp().to_string();
-                        self.human = dt.format(&self.format).to_string();
+                        self.instant = Some(dt.with_timezone(&Utc));
+                        self.epoch = epoch::format(&dt.with_timezone(&Utc), self.unit.unwrap_or(self.detected));
+                        self.epoch_error = None;
+                        self.note = None;
+                        self.format_human();
                     }
                     Err(_) => {}
                 }
             }
+            TimestampConverterMsg::ChangeUnit(unit) => {
+                self.unit = unit;
+                self.convert_epoch(self.epoch.clone());
+            }
+            TimestampConverterMsg::ChangeZone(ChangeData::Value(value)) => {
+                match zones::find(&value) {
+                    Some(zone) => {
+                        self.zone = zone;
+                        self.zone_error = false;
+                        self.note = None;
+                        self.format_human();
+                        self.store_zones();
+                    }
+                    None => self.zone_error = true,
+                }
+            }
+            TimestampConverterMsg::ChangeZone(_) => return false,
+            TimestampConverterMsg::ChangeNewZone(value) => {
+                self.new_zone = value;
+                self.new_zone_error = false;
+            }
+            TimestampConverterMsg::AddZone => {
+                match zones::find(&self.new_zone) {
+                    Some(zone) => {
+                        if !self.zones.contains(&zone) {
+                            self.zones.push(zone);
+                            self.store_zones();
+                        }
+                        self.new_zone.clear();
+                    }
+                    None => self.new_zone_error = !self.new_zone.trim().is_empty(),
+                }
+            }
+            TimestampConverterMsg::RemoveZone(index) => {
+                if index < self.zones.len() {
+                    self.zones.remove(index);
+                    self.store_zones();
+                }
+            }
         }
         true
     }
@@ -98,26 +192,28 @@ impl Component for TimestampConverter {
                     <div class="uk-form-controls">
                         <div class="uk-inline uk-width-expand">
                             <CopyToClipboard from="epoch" />
-                            <input class="uk-input uk-form-large mousetrap"
+                            <input class=if self.epoch_error.is_some() { "uk-input uk-form-large mousetrap uk-form-danger" } else { "uk-input uk-form-large mousetrap" }
                                 id="epoch"
                                 type="text"
                                 oninput=self.link.callback(|d: InputData| TimestampConverterMsg::ConvertEpoch(d.value))
                                 value=self.epoch.clone() />
                         </div>
                     </div>
+                    <div class="uk-text-danger uk-text-small">{ self.epoch_error.clone().unwrap_or_default() }</div>
                 </div>
                 <div class="uk-width-1-4">
                     <label class="uk-form-label" for="human">{ "Human" }</label>
                     <div class="uk-form-controls">
                         <div class="uk-inline uk-width-expand">
                             <CopyToClipboard from="human" />
-                            <input class="uk-input uk-form-large"
+                            <input class=if self.human_error.is_some() { "uk-input uk-form-large uk-form-danger" } else { "uk-input uk-form-large" }
                                 id="human"
                                 type="text"
                                 oninput=self.link.callback(|d: InputData| TimestampConverterMsg::ConvertHuman(d.value))
                                 value=self.human.clone() />
                         </div>
                     </div>
+                    <div class="uk-text-danger uk-text-small">{ self.human_error.clone().unwrap_or_default() }</div>
                 </div>
                 <div class="uk-width-1-3">
                     <label class="uk-form-label" for="format">{ "Format" }</label>
@@ -162,7 +258,176 @@ impl Component for TimestampConverter {
                         </div>
                     </div>
                 </div>
+                <div class="uk-width-1-4">
+                    <label class="uk-form-label" for="zone">{ "Time Zone" }</label>
+                    <div class="uk-form-controls">
+                        <input class=if self.zone_error { "uk-input uk-form-danger" } else { "uk-input" }
+                            id="zone"
+                            type="text"
+                            list="zonenames"
+                            onchange=self.link.callback(TimestampConverterMsg::ChangeZone)
+                            value=self.zone.name() />
+                    </div>
+                </div>
+                <div class="uk-width-1-4">
+                    <label class="uk-form-label">{ "Epoch Unit" }</label>
+                    <div class="uk-form-controls">
+                        <div class="uk-button-group">
+                            { self.unit_button(&format!("auto ({})", self.detected), None) }
+                            { for epoch::UNITS.iter().map(|unit| self.unit_button(&unit.to_string(), Some(*unit))) }
+                        </div>
+                    </div>
+                </div>
+                <div class="uk-width-1-2">
+                    <label class="uk-form-label">{ '\u{00a0}' }</label>
+                    <div class="uk-margin-small-top">
+                        { self.instant.map(|dt| self.view_offset(dt)).unwrap_or_default() }
+                        <span class="uk-text-muted uk-text-small uk-margin-small-left">{ self.note.clone().unwrap_or_default() }</span>
+                    </div>
+                </div>
+                <datalist id="zonenames">
+                    { for zones::names().map(|name| html! { <option value=name /> }) }
+                </datalist>
+                { self.view_zones() }
             </form>
         }
     }
-}
\ No newline at end of file
+}
+
+impl TimestampConverter {
+    /// Reads the epoch field in the selected unit, or the one detected by magnitude, and updates the human field
+    fn convert_epoch(&mut self, value: String) {
+        match epoch::parse(&value, self.unit) {
+            Ok((dt, unit)) => {
+                self.instant = Some(dt);
+                self.detected = unit;
+                self.epoch_error = None;
+                self.note = None;
+                self.format_human();
+            }
+            Err(_) if value.trim().is_empty() => self.epoch_error = None,
+            Err(e) => self.epoch_error = Some(e),
+        }
+
+        self.epoch = value;
+    }
+
+    /// Writes the instant in the human field in the selected zone and format, with fractional seconds if there are any
+    fn format_human(&mut self) {
+        if let Some(dt) = self.instant {
+            self.human = dt.with_timezone(&self.zone).format(&self.human_format()).to_string();
+            self.human_error = None;
+        }
+    }
+
+    fn human_format(&self) -> String {
+        epoch::with_fraction(&zones::zone_format(&self.format, self.zone))
+    }
+
+    /// Creates a button of the epoch unit selection
+    fn unit_button(&self, text: &str, unit: Option<Unit>) -> Html {
+        let class = if self.unit == unit { "uk-button uk-button-small uk-button-primary" } else { "uk-button uk-button-small uk-button-default" };
+
+        html! {
+            <button class=class
+                    type="button"
+                    onclick=self.link.callback(move |_| TimestampConverterMsg::ChangeUnit(unit))>
+                { text }
+            </button>
+        }
+    }
+
+    /// Reads the human field with the selected format, in the selected zone unless the input has an offset, and
+    /// returns the instant with a note on times that are ambiguous or skipped in the zone
+    fn parse_human(&self, value: &str) -> Result<(DateTime<Utc>, Option<String>), String> {
+        let format = self.human_format();
+        if let Ok(dt) = DateTime::parse_from_str(value, &format) {
+            return Ok((dt.with_timezone(&Utc), None));
+        }
+
+        // a pasted timestamp with the literal `Z` of the format is in UTC, whichever zone is selected
+        let utc_format = epoch::with_fraction(&self.format);
+        if utc_format != format {
+            if let Ok(utc) = NaiveDateTime::parse_from_str(value, &utc_format) {
+                return Ok((Utc.from_utc_datetime(&utc), None));
+            }
+        }
+
+        match NaiveDateTime::parse_from_str(value, &format) {
+            Ok(local) => zones::from_local(&local, self.zone).map(|(dt, note)| (dt.with_timezone(&Utc), note)),
+            Err(_) => Err(format!("the input does not match the format {}", zones::zone_format(&self.format, self.zone))),
+        }
+    }
+
+    /// Stores the selected zones in the local storage
+    fn store_zones(&mut self) {
+        if let Ok(storage) = &mut self.storage {
+            storage.store(TIME_ZONE_KEY, Json(&self.zone.name()));
+            storage.store(TIME_ZONES_KEY, Json(&self.zones.iter().map(|zone| zone.name()).collect::<Vec<_>>()))
+        }
+    }
+
+    /// Creates the offset of the instant in the zone with a label for daylight saving time
+    fn view_offset(&self, dt: DateTime<Utc>) -> Html {
+        let dt = dt.with_timezone(&self.zone);
+
+        html! {
+            <>
+                <span>{ zones::describe_offset(&dt) }</span>
+                { if zones::is_dst(&dt) { html! { <span class="uk-label uk-margin-small-left">{ "DST" }</span> } } else { html! {} } }
+            </>
+        }
+    }
+
+    /// Creates the table of the instant in the added zones side by side, with the input to add more of them
+    fn view_zones(&self) -> Html {
+        let format = |dt: DateTime<Tz>| dt.format("%a, %Y-%m-%d %H:%M:%S").to_string();
+
+        html! {
+            <div class="uk-width-1-1">
+                <table class="uk-table uk-table-small uk-table-divider uk-margin-remove">
+                    <tbody>
+                        { for self.zones.iter().enumerate().map(|(index, zone)| {
+                            let dt = self.instant.map(|dt| dt.with_timezone(zone));
+                            html! {
+                                <tr>
+                                    <td>{ zone.name() }</td>
+                                    <td>{ dt.map(format).unwrap_or_default() }</td>
+                                    <td>
+                                        { dt.map(|dt| zones::describe_offset(&dt)).unwrap_or_default() }
+                                        { if dt.is_some_and(|dt| zones::is_dst(&dt)) { html! { <span class="uk-label uk-margin-small-left">{ "DST" }</span> } } else { html! {} } }
+                                    </td>
+                                    <td class="uk-table-shrink">
+                                        <a href="#"
+                                           uk-icon="icon: close"
+                                           uk-tooltip="remove zone"
+                                           onclick=self.link.callback(move |e: MouseEvent| {
+                                               e.prevent_default();
+                                               TimestampConverterMsg::RemoveZone(index)
+                                           })>
+                                        </a>
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </tbody>
+                </table>
+                <div class="uk-flex uk-margin-small-top">
+                    <input class=if self.new_zone_error { "uk-input uk-form-small uk-form-width-medium uk-form-danger" } else { "uk-input uk-form-small uk-form-width-medium" }
+                        id="newzone"
+                        type="text"
+                        list="zonenames"
+                        placeholder="Europe/Berlin"
+                        oninput=self.link.callback(|d: InputData| TimestampConverterMsg::ChangeNewZone(d.value))
+                        onchange=self.link.callback(|_| TimestampConverterMsg::AddZone)
+                        value=self.new_zone.clone() />
+                    <button class="uk-button uk-button-small uk-button-default uk-margin-small-left"
+                            type="button"
+                            onclick=self.link.callback(|_| TimestampConverterMsg::AddZone)>
+                        { "add zone" }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::prelude::*;
+use std::fmt;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// The unit of an epoch timestamp
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Unit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+pub const UNITS: &[Unit] = &[Unit::Seconds, Unit::Milliseconds, Unit::Microseconds, Unit::Nanoseconds];
+
+impl Unit {
+    /// Returns the nanoseconds in one unit
+    fn nanos(self) -> i128 {
+        match self {
+            Unit::Seconds => NANOS_PER_SECOND,
+            Unit::Milliseconds => 1_000_000,
+            Unit::Microseconds => 1_000,
+            Unit::Nanoseconds => 1,
+        }
+    }
+
+    /// Guesses the unit by magnitude, seconds reach until the year 5138, so larger values are milliseconds from
+    /// JavaScript, microseconds, or nanoseconds from Go or Prometheus
+    pub fn detect(value: i128) -> Unit {
+        match value.unsigned_abs() {
+            0..=99_999_999_999 => Unit::Seconds,
+            100_000_000_000..=99_999_999_999_999 => Unit::Milliseconds,
+            100_000_000_000_000..=99_999_999_999_999_999 => Unit::Microseconds,
+            _ => Unit::Nanoseconds,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Seconds => write!(f, "s"),
+            Unit::Milliseconds => write!(f, "ms"),
+            Unit::Microseconds => write!(f, "\u{00b5}s"),
+            Unit::Nanoseconds => write!(f, "ns"),
+        }
+    }
+}
+
+/// Parses an epoch timestamp with an optional sign and fraction in the given unit, or in the detected one, and returns
+/// the instant with the unit used
+pub fn parse(input: &str, unit: Option<Unit>) -> Result<(DateTime<Utc>, Unit), String> {
+    let input = input.trim();
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    if let Some((index, c)) = integer.chars().chain(fraction.chars()).enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+        let position = input.len() - digits.len() + index + if index >= integer.len() { 2 } else { 1 };
+        return Err(format!("'{}' at position {} is not a digit", c, position));
+    }
+    let integer: i128 = match integer.parse() {
+        Ok(integer) => integer,
+        Err(_) if integer.is_empty() => return Err("digits are missing before the fraction".to_string()),
+        Err(_) => return Err("the timestamp is out of range".to_string()),
+    };
+
+    let unit = unit.unwrap_or_else(|| Unit::detect(integer));
+    // digits beyond nanoseconds are cut off
+    let fraction_nanos = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(18)
+        .collect::<String>()
+        .parse::<i128>()
+        .map_or(0, |attos| attos * unit.nanos() / 1_000_000_000_000_000_000);
+
+    let nanos = integer
+        .checked_mul(unit.nanos())
+        .and_then(|nanos| nanos.checked_add(fraction_nanos))
+        .ok_or_else(|| "the timestamp is out of range".to_string())?;
+    let nanos = if negative { -nanos } else { nanos };
+
+    let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).map_err(|_| "the timestamp is out of range".to_string())?;
+    match Utc.timestamp_opt(seconds, nanos.rem_euclid(NANOS_PER_SECOND) as u32).single() {
+        Some(dt) => Ok((dt, unit)),
+        None => Err("the timestamp is out of range".to_string()),
+    }
+}
+
+/// Writes the instant as epoch timestamp in the unit, with a fraction if it is not a whole unit
+pub fn format(dt: &DateTime<Utc>, unit: Unit) -> String {
+    let nanos = dt.timestamp() as i128 * NANOS_PER_SECOND + dt.timestamp_subsec_nanos() as i128;
+    let (whole, rest) = (nanos.div_euclid(unit.nanos()), nanos.rem_euclid(unit.nanos()));
+    if rest == 0 {
+        return whole.to_string();
+    }
+
+    // a negative instant with a fraction lies between the next whole unit towards zero and the one below it
+    let (whole, rest) = if nanos < 0 { (whole + 1, unit.nanos() - rest) } else { (whole, rest) };
+    let sign = if nanos < 0 && whole == 0 { "-" } else { "" };
+    let width = unit.nanos().to_string().len() - 1;
+    let fraction = format!("{:0>1$}", rest, width);
+
+    format!("{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+}
+
+/// Adds the fractional seconds after the seconds of a format, which are left out if they are zero and optional when
+/// parsing
+pub fn with_fraction(format: &str) -> String {
+    format.replace("%S", "%S%.f").replace("%T", "%T%.f")
+}
@@ -0,0 +1,74 @@
+use chrono::prelude::*;
+use chrono::LocalResult;
+use chrono_tz::{GapInfo, OffsetName, Tz, TZ_VARIANTS};
+
+/// Finds a time zone of the IANA database by its name, ignoring case
+pub fn find(name: &str) -> Option<Tz> {
+    let name = name.trim();
+    TZ_VARIANTS.iter().copied().find(|zone| zone.name().eq_ignore_ascii_case(name))
+}
+
+/// Returns the names of all time zones for the selection
+pub fn names() -> impl Iterator<Item = &'static str> {
+    TZ_VARIANTS.iter().map(|zone| zone.name())
+}
+
+/// Reads the local date and time in the zone, a time that occurs twice when the clocks are set back is the earlier
+/// one, and a time skipped when they are set forward is the first one after the gap, both with a note
+pub fn from_local(local: &NaiveDateTime, zone: Tz) -> Result<(DateTime<Tz>, Option<String>), String> {
+    match zone.from_local_datetime(local) {
+        LocalResult::Single(dt) => Ok((dt, None)),
+        LocalResult::Ambiguous(earlier, later) => {
+            let note = format!(
+                "{} occurs twice in {}, using the earlier {} and not {}",
+                local,
+                zone.name(),
+                describe_offset(&earlier),
+                describe_offset(&later)
+            );
+            Ok((earlier, Some(note)))
+        }
+        LocalResult::None => match GapInfo::new(local, &zone).and_then(|gap| gap.end) {
+            Some(end) => {
+                let note = format!("{} does not exist in {}, the clocks are set forward, using {}", local, zone.name(), end.naive_local());
+                Ok((end, Some(note)))
+            }
+            None => Err(format!("{} does not exist in {}", local, zone.name())),
+        },
+    }
+}
+
+/// Describes the offset to UTC with the abbreviation of the zone, e.g. `UTC+02:00 CEST`
+pub fn describe_offset(dt: &DateTime<Tz>) -> String {
+    let seconds = dt.offset().fix().local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let offset = format!("UTC{}{:02}:{:02}", sign, seconds.abs() / 3600, seconds.abs() % 3600 / 60);
+
+    match dt.offset().abbreviation() {
+        // zones without an abbreviation of their own use the offset, e.g. `+03`
+        Some(abbreviation) if abbreviation.starts_with(char::is_alphabetic) => format!("{} {}", offset, abbreviation),
+        _ => offset,
+    }
+}
+
+/// Tells whether daylight saving time is in effect, i.e. the offset is above the standard offset of the zone, which is
+/// the smaller one of January and July, as zones like Europe/Dublin define their winter time as negative saving time
+pub fn is_dst(dt: &DateTime<Tz>) -> bool {
+    let offset_in = |month: u32| {
+        let utc = NaiveDate::from_ymd_opt(dt.year(), month, 1)?.and_hms_opt(0, 0, 0)?;
+        Some(dt.timezone().from_utc_datetime(&utc).offset().fix().local_minus_utc())
+    };
+
+    match (offset_in(1), offset_in(7)) {
+        (Some(january), Some(july)) => dt.offset().fix().local_minus_utc() > january.min(july),
+        _ => false,
+    }
+}
+
+/// Adapts a format that ends in a literal `Z` for UTC to show the offset of other zones instead
+pub fn zone_format(format: &str, zone: Tz) -> String {
+    match format.strip_suffix('Z') {
+        Some(prefix) if zone != Tz::UTC && !prefix.ends_with('%') => format!("{}%:z", prefix),
+        _ => format.to_string(),
+    }
+}
@@ -1,8 +1,10 @@
 mod components;
 mod theme;
 
+use crate::components::byteconverter::ByteConverter;
 use crate::components::chmodcalculator::ChmodCalculator;
 use crate::components::colorhelper::ColorHelper;
+use crate::components::floatinspector::FloatInspector;
 use crate::components::ipcalculator::IpCalculator;
 use crate::components::navbar::Navbar;
 use crate::components::numcalculator::NumCalculator;
@@ -102,6 +104,16 @@ impl Component for Model {
                     <div class="uk-container">
                         <NumConverter />
                     </div>
+                    <div class="uk-container uk-margin-top">
+                        <ByteConverter />
+                    </div>
+                    <h3 class="uk-heading-divider">
+                        <a id="floatinspector"></a>
+                        { "Float Inspector" }
+                    </h3>
+                    <div class="uk-container">
+                        <FloatInspector />
+                    </div>
                     <h3 class="uk-heading-divider">
                         <a id="ipcalculator"></a>
                         { "IP Calculator" }
@@ -33,22 +33,45 @@ export function getCurrentUTC() {
 }
 
 /**
- * Queries an API to get the IP address of the user agent.
+ * Gathers the local addresses of the user agent as visible to WebRTC ICE candidates. Browsers that hide local
+ * addresses report mDNS host names instead.
  * 
- * @return the IPv4 address of the user agent or an empty string in case of an error
+ * @param {Function} callback called once with a comma-separated list of addresses or an empty string
  */
-export function getMyIP() {
-    var data = $.ajax({
-        url: 'https://api.db-ip.com/v2/free/self',
-        async: false
-    }).responseText;
-
-    try {
-        var json = JSON.parse(data);
-        return json["ipAddress"];
-    } catch (e) {
-        return "";
+export function getLocalIPs(callback) {
+    if (!window.RTCPeerConnection) {
+        callback("");
+        return;
     }
+
+    var addresses = new Set();
+    var done = false;
+    var connection = new RTCPeerConnection({ iceServers: [] });
+
+    var finish = function () {
+        if (done)
+            return;
+        done = true;
+        connection.close();
+        callback(Array.from(addresses).join(", "));
+    };
+
+    connection.onicecandidate = function (event) {
+        if (!event.candidate || !event.candidate.candidate)
+            return finish();
+
+        /* candidate:<foundation> <component> <protocol> <priority> <address> <port> typ <type> ... */
+        var parts = event.candidate.candidate.split(" ");
+        if (parts.length > 4)
+            addresses.add(parts[4]);
+    };
+
+    connection.createDataChannel("");
+    connection.createOffer()
+        .then(function (offer) { return connection.setLocalDescription(offer); })
+        .catch(finish);
+
+    setTimeout(finish, 3000);
 }
 
 /**